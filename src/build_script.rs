@@ -0,0 +1,99 @@
+use crate::{
+    error::{GeneratorError, Result},
+    formatter::format_tokens,
+    graphql::generate_graphql_module,
+    plugin::{apply_plugins, Plugins},
+    types::SchemaSnapshot,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::{collections::BTreeMap, env, fs, io, path::Path};
+
+// written to `OUT_DIR` with the `orm` and `graphql` modules, the crate includes it at its root next to its `extensions`:
+// `include!(concat!(env!("OUT_DIR"), "/graphql_api.rs"));`
+pub const GRAPHQL_API_FILE: &str = "graphql_api.rs";
+
+// to be called from `build.rs`, the snapshot is created with `cargo run snapshot <file>`
+pub fn generate_graphql_api(snapshot_path: impl AsRef<Path>) -> Result<()> {
+    generate_graphql_api_with_plugins(snapshot_path, &[])
+}
+
+pub fn generate_graphql_api_with_plugins(snapshot_path: impl AsRef<Path>, plugins: &Plugins) -> Result<()> {
+    let snapshot_path = snapshot_path.as_ref();
+
+    println!("cargo:rerun-if-changed={}", snapshot_path.display());
+
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        GeneratorError::Io(io::Error::new(io::ErrorKind::NotFound, "OUT_DIR is not set, call this from a build script"))
+    })?;
+
+    let tokens = generate_graphql_api_tokens(&SchemaSnapshot::load(snapshot_path)?, plugins)?;
+
+    fs::write(Path::new(&out_dir).join(GRAPHQL_API_FILE), format_tokens(GRAPHQL_API_FILE, &tokens)?)?;
+
+    Ok(())
+}
+
+// the item hooks of the plugins are applied to the metadata of the snapshot
+pub fn generate_graphql_api_tokens(snapshot: &SchemaSnapshot, plugins: &Plugins) -> Result<TokenStream> {
+    if snapshot.tables_meta.is_empty() {
+        return Err(GeneratorError::InvalidSnapshot(
+            "the snapshot has no generator metadata, recreate it with `cargo run snapshot <file>`".into(),
+        ));
+    }
+
+    if snapshot.orm_files.is_empty() {
+        return Err(GeneratorError::InvalidSnapshot(
+            "the snapshot has no orm sources, recreate it with `cargo run snapshot <file>`".into(),
+        ));
+    }
+
+    let tables_meta = apply_plugins(plugins, snapshot.tables_meta.clone());
+
+    let orm = generate_orm_module(&snapshot.orm_files)?;
+    let graphql = generate_graphql_module(&tables_meta, plugins)?;
+
+    Ok(quote! {
+        #orm
+
+        #graphql
+    })
+}
+
+// the files of sea-orm-codegen as inline modules, `mod.rs` declares them
+fn generate_orm_module(orm_files: &BTreeMap<String, String>) -> Result<TokenStream> {
+    let mut attrs: Vec<syn::Attribute> = Vec::new();
+    let mut items: Vec<TokenStream> = Vec::new();
+
+    for (name, content) in orm_files.iter() {
+        let file: syn::File = syn::parse_file(content).map_err(|error| GeneratorError::Formatting {
+            file: format!("orm/{}", name),
+            message: error.to_string(),
+        })?;
+
+        if name.eq("mod.rs") {
+            let declarations = file.items.iter().filter(|item| !matches!(item, syn::Item::Mod(_)));
+
+            attrs.extend(file.attrs.iter().cloned());
+            items.push(quote!(#(#declarations)*));
+        } else {
+            let module = format_ident!("{}", name.trim_end_matches(".rs"));
+            let attrs = file.attrs.iter();
+            let file_items = file.items.iter();
+
+            items.push(quote! {
+                pub mod #module {
+                    #(#attrs)*
+                    #(#file_items)*
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        pub mod orm {
+            #(#attrs)*
+            #(#items)*
+        }
+    })
+}
@@ -0,0 +1,517 @@
+use crate::{
+    graphql::entities::{get_hierarchy_relation_names, get_relation_directions},
+    scalar_mapping::{get_json_type, get_scalar},
+    types::{ColumnMeta, ForeignKeyMeta, ManyToManyMeta, TableMeta},
+};
+use heck::ToLowerCamelCase;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use std::collections::BTreeMap;
+
+// rust type of a column (without `Option`) -> type the client deserializes it into and
+// whether the server exposes a `TypeFilter` for it, scalars serialized as strings stay strings
+fn get_client_type(column_type: &str) -> (TokenStream, bool) {
+    let (scalar, filter) = get_scalar(column_type);
+
+    let client_type = match get_json_type(scalar) {
+        "string" => quote! { String },
+        _ => {
+            let column_type = format_ident!("{}", column_type);
+
+            quote! { #column_type }
+        }
+    };
+
+    (client_type, filter.is_some())
+}
+
+// every file of the client crate, keyed by its path relative to the crate directory
+pub fn generate_client(tables_meta: &[TableMeta]) -> BTreeMap<String, TokenStream> {
+    let mut files: BTreeMap<String, TokenStream> = BTreeMap::new();
+
+    let entity_modules: Vec<Ident> = tables_meta
+        .iter()
+        .map(|table: &TableMeta| format_ident!("{}", table.entity_module))
+        .collect();
+
+    for table in tables_meta.iter() {
+        files.insert(
+            format!("src/entities/{}.rs", table.entity_module),
+            generate_client_entity(table),
+        );
+    }
+
+    files.insert("src/entities/mod.rs".into(), quote! {
+        #(pub mod #entity_modules;)*
+    });
+
+    files.insert("src/lib.rs".into(), generate_client_lib(tables_meta));
+
+    files
+}
+
+pub fn generate_client_lib(tables_meta: &[TableMeta]) -> TokenStream {
+    let root_queries: Vec<TokenStream> = tables_meta
+        .iter()
+        .map(|table: &TableMeta| {
+            let entity_module = format_ident!("{}", table.entity_module);
+
+            quote! {
+                pub fn #entity_module(&self) -> entities::#entity_module::Query<'_> {
+                    entities::#entity_module::Query::new(self)
+                }
+            }
+        })
+        .collect();
+
+    quote! {
+        pub mod entities;
+
+        use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+        #[derive(Clone, Debug, Serialize)]
+        pub struct Request {
+            pub query: String,
+            pub variables: serde_json::Value,
+        }
+
+        #[derive(Clone, Debug, Deserialize)]
+        pub struct GraphQLError {
+            pub message: String,
+        }
+
+        #[derive(Debug)]
+        pub enum ClientError {
+            Transport(String),
+            GraphQL(Vec<GraphQLError>),
+            Deserialize(serde_json::Error),
+        }
+
+        impl std::fmt::Display for ClientError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    ClientError::Transport(message) => write!(f, "transport error: {}", message),
+                    ClientError::GraphQL(errors) => {
+                        let messages: Vec<&str> = errors
+                            .iter()
+                            .map(|error| error.message.as_str())
+                            .collect();
+
+                        write!(f, "graphql errors: {}", messages.join(", "))
+                    }
+                    ClientError::Deserialize(error) => write!(f, "invalid response: {}", error),
+                }
+            }
+        }
+
+        impl std::error::Error for ClientError {}
+
+        // sends a request and returns the raw GraphQL response (`data` and `errors`)
+        #[async_trait::async_trait]
+        pub trait Transport: Send + Sync {
+            async fn execute(&self, request: Request) -> Result<serde_json::Value, ClientError>;
+        }
+
+        pub struct HttpTransport {
+            url: String,
+            client: reqwest::Client,
+        }
+
+        impl HttpTransport {
+            pub fn new(url: impl Into<String>) -> Self {
+                Self {
+                    url: url.into(),
+                    client: reqwest::Client::new(),
+                }
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl Transport for HttpTransport {
+            async fn execute(&self, request: Request) -> Result<serde_json::Value, ClientError> {
+                self.client
+                    .post(&self.url)
+                    .json(&request)
+                    .send()
+                    .await
+                    .map_err(|error| ClientError::Transport(error.to_string()))?
+                    .json::<serde_json::Value>()
+                    .await
+                    .map_err(|error| ClientError::Transport(error.to_string()))
+            }
+        }
+
+        pub struct Client {
+            transport: Box<dyn Transport>,
+        }
+
+        impl Client {
+            pub fn new(transport: impl Transport + 'static) -> Self {
+                Self {
+                    transport: Box::new(transport),
+                }
+            }
+
+            pub fn http(url: impl Into<String>) -> Self {
+                Self::new(HttpTransport::new(url))
+            }
+
+            // deserializes `data.<field>` of the response
+            pub async fn execute<T: DeserializeOwned>(&self, request: Request, field: &str) -> Result<T, ClientError> {
+                let mut response = self.transport.execute(request).await?;
+
+                if let Some(errors) = response.get("errors") {
+                    let errors: Vec<GraphQLError> = serde_json::from_value(errors.clone())
+                        .map_err(ClientError::Deserialize)?;
+
+                    if !errors.is_empty() {
+                        return Err(ClientError::GraphQL(errors));
+                    }
+                }
+
+                let data = response
+                    .get_mut("data")
+                    .and_then(|data| data.get_mut(field))
+                    .map(serde_json::Value::take)
+                    .unwrap_or(serde_json::Value::Null);
+
+                serde_json::from_value(data).map_err(ClientError::Deserialize)
+            }
+
+            #(#root_queries)*
+        }
+
+        #[derive(Clone, Debug, Default, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct TypeFilter<T> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub eq: Option<T>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub ne: Option<T>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub gt: Option<T>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub gte: Option<T>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub lt: Option<T>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub lte: Option<T>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub is_in: Option<Vec<T>>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub is_not_in: Option<Vec<T>>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub is_null: Option<bool>,
+        }
+
+        impl<T> TypeFilter<T> {
+            pub fn eq(value: T) -> Self {
+                Self { eq: Some(value), ..Self::empty() }
+            }
+
+            pub fn ne(value: T) -> Self {
+                Self { ne: Some(value), ..Self::empty() }
+            }
+
+            pub fn gt(value: T) -> Self {
+                Self { gt: Some(value), ..Self::empty() }
+            }
+
+            pub fn gte(value: T) -> Self {
+                Self { gte: Some(value), ..Self::empty() }
+            }
+
+            pub fn lt(value: T) -> Self {
+                Self { lt: Some(value), ..Self::empty() }
+            }
+
+            pub fn lte(value: T) -> Self {
+                Self { lte: Some(value), ..Self::empty() }
+            }
+
+            pub fn is_in(values: Vec<T>) -> Self {
+                Self { is_in: Some(values), ..Self::empty() }
+            }
+
+            pub fn is_not_in(values: Vec<T>) -> Self {
+                Self { is_not_in: Some(values), ..Self::empty() }
+            }
+
+            pub fn is_null(is_null: bool) -> Self {
+                Self { is_null: Some(is_null), ..Self::empty() }
+            }
+
+            fn empty() -> Self {
+                Self {
+                    eq: None,
+                    ne: None,
+                    gt: None,
+                    gte: None,
+                    lt: None,
+                    lte: None,
+                    is_in: None,
+                    is_not_in: None,
+                    is_null: None,
+                }
+            }
+        }
+
+        #[derive(Clone, Debug, Serialize)]
+        pub struct PaginationInput {
+            pub limit: usize,
+            pub page: usize,
+        }
+
+        #[derive(Clone, Debug, Deserialize)]
+        pub struct PaginatedResult<T> {
+            pub data: Vec<T>,
+            pub pages: usize,
+            pub current: usize,
+        }
+    }
+}
+
+pub fn generate_client_entity(table: &TableMeta) -> TokenStream {
+    let root_field = table.entity_module.to_lower_camel_case();
+    let filter_name = table.filter_name.clone();
+
+    let model_fields: Vec<TokenStream> = generate_model_fields(table);
+    let filter_fields: Vec<TokenStream> = generate_filter_fields(table);
+    let selection_relations: Vec<TokenStream> = generate_selection_relations(table);
+
+    let selected_fields: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column: &ColumnMeta| column.field_name.to_lower_camel_case())
+        .collect();
+
+    quote! {
+        use serde::{Deserialize, Serialize};
+
+        // relation fields stay empty unless they are added to the `Selection`
+        #[derive(Clone, Debug, Deserialize)]
+        pub struct Model {
+            #(#model_fields,)*
+        }
+
+        #[derive(Clone, Debug, Default, Serialize)]
+        pub struct Filter {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub or: Option<Vec<Filter>>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub and: Option<Vec<Filter>>,
+            #(#filter_fields,)*
+        }
+
+        // fields requested for every model, all columns plus the chosen relations
+        #[derive(Clone, Debug, Default)]
+        pub struct Selection {
+            relations: Vec<String>,
+        }
+
+        impl Selection {
+            pub fn to_graphql(&self) -> String {
+                let mut fields: Vec<String> = vec![#(#selected_fields.into()),*];
+
+                fields.extend(self.relations.iter().cloned());
+
+                fields.join(" ")
+            }
+
+            #(#selection_relations)*
+        }
+
+        pub struct Query<'a> {
+            client: &'a crate::Client,
+            filters: Option<Filter>,
+            pagination: Option<crate::PaginationInput>,
+            selection: Selection,
+        }
+
+        impl<'a> Query<'a> {
+            pub fn new(client: &'a crate::Client) -> Self {
+                Self {
+                    client,
+                    filters: None,
+                    pagination: None,
+                    selection: Selection::default(),
+                }
+            }
+
+            pub fn filters(mut self, filters: Filter) -> Self {
+                self.filters = Some(filters);
+                self
+            }
+
+            pub fn pagination(mut self, limit: usize, page: usize) -> Self {
+                self.pagination = Some(crate::PaginationInput { limit, page });
+                self
+            }
+
+            pub fn select(mut self, selection: Selection) -> Self {
+                self.selection = selection;
+                self
+            }
+
+            pub fn to_request(&self) -> crate::Request {
+                let query = format!(
+                    "query($filters: {}, $pagination: PaginationInput) {{ {}(filters: $filters, pagination: $pagination) {{ data {{ {} }} pages current }} }}",
+                    #filter_name,
+                    #root_field,
+                    self.selection.to_graphql()
+                );
+
+                crate::Request {
+                    query,
+                    variables: serde_json::json!({
+                        "filters": self.filters,
+                        "pagination": self.pagination,
+                    }),
+                }
+            }
+
+            pub async fn send(self) -> Result<crate::PaginatedResult<Model>, crate::ClientError> {
+                self.client.execute(self.to_request(), #root_field).await
+            }
+        }
+    }
+}
+
+// destination module, GraphQL field, method name and whether the relation is a list
+fn get_client_relations(table: &TableMeta) -> Vec<(String, String, Ident, bool)> {
+    let mut relations: Vec<(String, String, Ident, bool)> = Vec::new();
+
+    for (fk, reverse) in get_relation_directions(table).into_iter() {
+        let ForeignKeyMeta { source_table_module, destination_table_module, .. } = fk;
+
+        let destination = if reverse { source_table_module } else { destination_table_module };
+        let relation_name = fk.get_relation_name(reverse);
+
+        relations.push((
+            destination.clone(),
+            relation_name.to_lower_camel_case(),
+            format_ident!("{}", relation_name),
+            fk.is_list(reverse),
+        ));
+    }
+
+    for relation in table.many_to_many.iter() {
+        let ManyToManyMeta { relation_name, destination_foreign_key, .. } = relation;
+
+        relations.push((
+            destination_foreign_key.destination_table_module.clone(),
+            relation_name.to_lower_camel_case(),
+            format_ident!("{}", relation_name),
+            true,
+        ));
+    }
+
+    relations
+}
+
+fn generate_model_fields(table: &TableMeta) -> Vec<TokenStream> {
+    let mut fields: Vec<TokenStream> = table
+        .columns
+        .iter()
+        .map(|column: &ColumnMeta| {
+            let field_name = format_ident!("{}", column.field_name);
+            let graphql_name = column.field_name.to_lower_camel_case();
+
+            let (field_type, _) = get_client_type(&column.column_filter_type.to_string());
+
+            let field_type = if column.not_null { field_type } else { quote! { Option<#field_type> } };
+
+            quote! {
+                #[serde(rename = #graphql_name)]
+                pub #field_name: #field_type
+            }
+        })
+        .collect();
+
+    for (destination, graphql_name, field_name, is_list) in get_client_relations(table).into_iter() {
+        let destination = format_ident!("{}", destination);
+
+        let field_type = if is_list {
+            quote! { Vec<super::#destination::Model> }
+        } else {
+            quote! { Option<Box<super::#destination::Model>> }
+        };
+
+        fields.push(quote! {
+            #[serde(rename = #graphql_name, default)]
+            pub #field_name: #field_type
+        });
+    }
+
+    for (_, ancestors, descendants) in get_hierarchy_relation_names(table).into_iter() {
+        for relation_name in [ancestors, descendants] {
+            let field_name = format_ident!("{}", relation_name);
+            let graphql_name = relation_name.to_lower_camel_case();
+
+            fields.push(quote! {
+                #[serde(rename = #graphql_name, default)]
+                pub #field_name: Vec<Model>
+            });
+        }
+    }
+
+    fields
+}
+
+fn generate_filter_fields(table: &TableMeta) -> Vec<TokenStream> {
+    table
+        .columns
+        .iter()
+        .filter_map(|column: &ColumnMeta| {
+            let (field_type, filterable) = get_client_type(&column.column_filter_type.to_string());
+
+            if !filterable {
+                return None;
+            }
+
+            let field_name = format_ident!("{}", column.field_name);
+            let graphql_name = column.field_name.to_lower_camel_case();
+
+            Some(quote! {
+                #[serde(rename = #graphql_name, skip_serializing_if = "Option::is_none")]
+                pub #field_name: Option<crate::TypeFilter<#field_type>>
+            })
+        })
+        .collect()
+}
+
+fn generate_selection_relations(table: &TableMeta) -> Vec<TokenStream> {
+    let mut methods: Vec<TokenStream> = get_client_relations(table)
+        .into_iter()
+        .map(|(destination, graphql_name, method_name, _)| {
+            let destination = format_ident!("{}", destination);
+
+            quote! {
+                pub fn #method_name(mut self, selection: super::#destination::Selection) -> Self {
+                    self.relations.push(format!("{} {{ {} }}", #graphql_name, selection.to_graphql()));
+                    self
+                }
+            }
+        })
+        .collect();
+
+    for (_, ancestors, descendants) in get_hierarchy_relation_names(table).into_iter() {
+        for relation_name in [ancestors, descendants] {
+            let method_name = format_ident!("{}", relation_name);
+            let graphql_name = relation_name.to_lower_camel_case();
+
+            methods.push(quote! {
+                pub fn #method_name(mut self, depth: Option<usize>, selection: Selection) -> Self {
+                    let arguments = depth
+                        .map(|depth| format!("(depth: {})", depth))
+                        .unwrap_or_default();
+
+                    self.relations.push(format!("{}{} {{ {} }}", #graphql_name, arguments, selection.to_graphql()));
+                    self
+                }
+            });
+        }
+    }
+
+    methods
+}
@@ -0,0 +1,276 @@
+use crate::error::{GeneratorError, Result};
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const CONFIG_FILE_NAME: &str = "graphql-generator.toml";
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct GeneratorConfig {
+    #[serde(default)]
+    pub infer_relations: bool,
+
+    // database table names, every table is generated when missing
+    pub include_tables: Option<Vec<String>>,
+    #[serde(default)]
+    pub exclude_tables: Vec<String>,
+
+    #[serde(default)]
+    pub naming: NamingConfig,
+
+    // keyed by database table name
+    #[serde(default)]
+    pub tables: BTreeMap<String, TableConfig>,
+
+    #[serde(default)]
+    pub relations: Vec<RelationConfig>,
+
+    // server the router of `lib.rs` is built for, the endpoints are the same for all of them
+    #[serde(default)]
+    pub web_framework: WebFramework,
+
+    // `library` leaves out `main.rs`, the crate is mounted by an existing server
+    #[serde(default)]
+    pub output: OutputMode,
+
+    // schema coordinates (`Type`, `Type.field`) whose breaking changes `schema-check` accepts
+    #[serde(default)]
+    pub allowed_breaking_changes: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NamingConfig {
+    pub filter: String,           // `{}` is replaced by the entity name
+    pub paginated_result: String, // `{}` is replaced by the entity name
+    pub strip_id_suffix: bool,    // `artist_id` -> `artist` instead of `artist_id_artist`
+}
+
+impl Default for NamingConfig {
+    fn default() -> Self {
+        Self {
+            filter: "{}Filter".into(),
+            paginated_result: "Paginated{}Result".into(),
+            strip_id_suffix: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct TableConfig {
+    pub name: Option<String>, // GraphQL entity name
+
+    #[serde(default)]
+    pub exclude_columns: Vec<String>,
+
+    // keyed by database column name
+    #[serde(default)]
+    pub columns: BTreeMap<String, ColumnConfig>,
+
+    // generated relation name -> new name
+    #[serde(default)]
+    pub relations: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ColumnConfig {
+    pub name: Option<String>, // GraphQL field name
+
+    // SQL type used instead of the declared one, e.g. "BIGINT"
+    #[serde(rename = "type")]
+    pub column_type: Option<String>,
+}
+
+// relation the database cannot express as a constraint, e.g. across denormalized columns
+#[derive(Deserialize, Debug, Clone)]
+pub struct RelationConfig {
+    pub source_table: String,
+    pub source_columns: Vec<String>,
+    pub destination_table: String,
+    pub destination_columns: Vec<String>,
+
+    #[serde(default)]
+    pub cardinality: Cardinality,
+
+    pub name: Option<String>,         // field on source table
+    pub reverse_name: Option<String>, // field on destination table
+}
+
+impl RelationConfig {
+    // `name` when given, e.g. "invoices.BillingCountry -> countries.Name" otherwise
+    pub fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!(
+                "{}.{} -> {}.{}",
+                self.source_table,
+                self.source_columns.join(","),
+                self.destination_table,
+                self.destination_columns.join(",")
+            ),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Cardinality {
+    #[default]
+    ManyToOne,
+    OneToOne,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebFramework {
+    #[default]
+    Poem,
+    Axum,
+    ActixWeb,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    #[default]
+    Binary,
+    Library,
+}
+
+impl GeneratorConfig {
+    // a missing file means default configuration
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+
+        toml::from_str(&content).map_err(|error| GeneratorError::InvalidConfig(error.to_string()))
+    }
+
+    pub fn is_table_included(&self, table_name: &String) -> bool {
+        let included = match &self.include_tables {
+            Some(include_tables) => include_tables.contains(table_name),
+            None => true,
+        };
+
+        included && !self.exclude_tables.contains(table_name)
+    }
+
+    pub fn get_table(&self, table_name: &str) -> Option<&TableConfig> {
+        self.tables.get(table_name)
+    }
+
+    pub fn get_column(&self, table_name: &str, column_name: &str) -> Option<&ColumnConfig> {
+        self.get_table(table_name)
+            .and_then(|table: &TableConfig| table.columns.get(column_name))
+    }
+
+    pub fn is_breaking_change_allowed(&self, coordinate: &str) -> bool {
+        self.allowed_breaking_changes.iter().any(|allowed: &String| {
+            coordinate.eq(allowed)
+                || coordinate.starts_with(&format!("{}.", allowed))
+                || coordinate.starts_with(&format!("{}(", allowed))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cardinality, GeneratorConfig, OutputMode, WebFramework};
+
+    fn parse(content: &str) -> GeneratorConfig {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn empty_file_is_the_default_configuration() {
+        let config = parse("");
+
+        assert!(!config.infer_relations);
+        assert!(config.include_tables.is_none());
+        assert_eq!(config.naming.filter, "{}Filter");
+        assert_eq!(config.naming.paginated_result, "Paginated{}Result");
+        assert!(config.naming.strip_id_suffix);
+        assert_eq!(config.web_framework, WebFramework::Poem);
+        assert_eq!(config.output, OutputMode::Binary);
+    }
+
+    #[test]
+    fn readme_example() {
+        let config = parse(
+            r#"
+            infer_relations = true
+            include_tables = ["albums", "artists", "tracks"]
+            exclude_tables = ["sqlite_stat1"]
+            web_framework = "actix_web"
+            output = "library"
+
+            [naming]
+            strip_id_suffix = false
+
+            [tables.employees]
+            name = "Employee"
+            exclude_columns = ["Fax"]
+            relations = { reports_to_employee = "manager" }
+
+            [tables.employees.columns.BirthDate]
+            name = "born_at"
+            type = "TEXT"
+
+            [[relations]]
+            source_table = "invoices"
+            source_columns = ["BillingCountry"]
+            destination_table = "countries"
+            destination_columns = ["Name"]
+            cardinality = "one_to_one"
+            "#,
+        );
+
+        assert!(config.infer_relations);
+        assert_eq!(config.web_framework, WebFramework::ActixWeb);
+        assert_eq!(config.output, OutputMode::Library);
+        // unset naming keys keep their defaults
+        assert_eq!(config.naming.filter, "{}Filter");
+        assert!(!config.naming.strip_id_suffix);
+
+        let employees = config.get_table("employees").unwrap();
+        assert_eq!(employees.name.as_deref(), Some("Employee"));
+        assert_eq!(employees.relations.get("reports_to_employee").map(String::as_str), Some("manager"));
+
+        let birth_date = config.get_column("employees", "BirthDate").unwrap();
+        assert_eq!(birth_date.name.as_deref(), Some("born_at"));
+        assert_eq!(birth_date.column_type.as_deref(), Some("TEXT"));
+
+        assert_eq!(config.relations[0].cardinality, Cardinality::OneToOne);
+        assert_eq!(config.relations[0].describe(), "invoices.BillingCountry -> countries.Name");
+    }
+
+    #[test]
+    fn unknown_values_are_rejected() {
+        assert!(toml::from_str::<GeneratorConfig>("web_framework = \"rocket\"").is_err());
+        assert!(toml::from_str::<GeneratorConfig>("[[relations]]\nsource_table = \"invoices\"").is_err());
+    }
+
+    #[test]
+    fn included_tables() {
+        let config = parse("include_tables = [\"albums\", \"artists\"]\nexclude_tables = [\"artists\"]");
+
+        assert!(config.is_table_included(&"albums".to_string()));
+        assert!(!config.is_table_included(&"artists".to_string()));
+        assert!(!config.is_table_included(&"tracks".to_string()));
+        assert!(parse("").is_table_included(&"tracks".to_string()));
+    }
+
+    #[test]
+    fn allowed_breaking_changes_cover_fields_and_arguments() {
+        let config = parse("allowed_breaking_changes = [\"Albums.title\", \"Genres\"]");
+
+        assert!(config.is_breaking_change_allowed("Albums.title"));
+        assert!(config.is_breaking_change_allowed("Albums.title(format:)"));
+        assert!(config.is_breaking_change_allowed("Genres.name"));
+        assert!(!config.is_breaking_change_allowed("Albums.titles"));
+        assert!(!config.is_breaking_change_allowed("GenresFilter"));
+    }
+}
@@ -1,56 +1,102 @@
-use crate::types::{ColumnMeta, ForeignKeyMeta, TableMeta};
+use crate::error::{GeneratorError, Result};
+use crate::config::{Cardinality, ColumnConfig, GeneratorConfig, RelationConfig, TableConfig};
+use crate::types::{ColumnMeta, ForeignKeyMeta, ManyToManyMeta, TableMeta};
 use heck::{ToSnakeCase, ToUpperCamelCase};
 use sea_orm_codegen::Column;
 use sea_query::{
-    ColumnDef, ColumnSpec, ForeignKeyCreateStatement, TableCreateStatement, TableForeignKey,
+    Alias, ColumnDef, ColumnSpec, ForeignKey, ForeignKeyCreateStatement, Table,
+    TableCreateStatement, TableForeignKey,
 };
-use sea_schema::sqlite::def::{Schema, TableDef};
+use sea_schema::sqlite::def::{ColumnInfo, Schema, TableDef, Type};
 use sea_schema::sqlite::discovery::SchemaDiscovery;
 use sqlx::{Pool, Sqlite};
 
+// `infer_relations` links `<table>_id` / `<Table>Id` columns without a foreign key constraint
+// to the primary key of the matching table, the inferred keys are returned as a report
 pub async fn get_database_schema(
     connection: Pool<Sqlite>,
-) -> (Vec<TableMeta>, Vec<TableCreateStatement>) {
+    config: &GeneratorConfig,
+) -> Result<(Vec<TableMeta>, Vec<TableCreateStatement>, Vec<ForeignKeyMeta>)> {
     let schema_discovery = SchemaDiscovery::new(connection);
 
-    let schema: Schema = schema_discovery.discover().await.unwrap();
+    let schema: Schema = schema_discovery
+        .discover()
+        .await
+        .map_err(|error| GeneratorError::Discovery(error.to_string()))?;
 
-    let table_create_stmt_map : std::collections::HashMap<String, TableCreateStatement> = schema
+    let tables: Vec<TableDef> = schema
         .tables
         .iter()
-        .map(|table: &TableDef| (table.name.clone(), table.write()))
+        .filter(|table: &&TableDef| config.is_table_included(&table.name))
+        .map(|table: &TableDef| apply_table_config(table, config))
+        .collect::<Result<Vec<TableDef>>>()?;
+
+    let table_create_stmt_map : std::collections::HashMap<String, TableCreateStatement> = tables
+        .iter()
+        .map(|table: &TableDef| (table.name.clone(), write_table(table, &tables)))
         .collect();
 
-    let foreign_keys: Vec<ForeignKeyMeta> = schema
-        .tables
-        .clone()
+    let foreign_keys: Vec<ForeignKeyMeta> = tables
         .iter()
         .map(|table: &TableDef| {
-            let table_create_stmt = table.write();
+            let table_create_stmt = get_table_create_stmt(&table_create_stmt_map, &table.name)?;
 
-            let foreign_keys: Vec<ForeignKeyMeta> = table_create_stmt
+            table_create_stmt
                 .get_foreign_key_create_stmts()
                 .iter()
                 .map(|fk: &ForeignKeyCreateStatement| fk.get_foreign_key())
-                .map(|fk: &TableForeignKey| parse_table_fk(&table, fk, &table_create_stmt_map))
-                .collect();
-
-            foreign_keys
+                .map(|fk: &TableForeignKey| parse_table_fk(table, fk, &table_create_stmt_map, config))
+                .collect::<Result<Vec<ForeignKeyMeta>>>()
         })
-        .fold(
-            Vec::<ForeignKeyMeta>::new(),
-            |acc: Vec<ForeignKeyMeta>, cur: Vec<ForeignKeyMeta>| {
-                [acc, cur].concat()
-            },
-        );
+        .collect::<Result<Vec<Vec<ForeignKeyMeta>>>>()?
+        .concat();
 
-    let tables_meta = schema
-        .tables
+    let virtual_foreign_keys: Vec<ForeignKeyMeta> = config
+        .relations
+        .iter()
+        .enumerate()
+        .map(|(index, relation): (usize, &RelationConfig)| parse_virtual_fk(index, relation, &tables, &table_create_stmt_map, config))
+        .collect::<Result<Vec<ForeignKeyMeta>>>()?;
+
+    check_virtual_fks(&virtual_foreign_keys, &foreign_keys, config)?;
+
+    // columns of declared relations are not inferred again
+    let inferred_foreign_keys: Vec<ForeignKeyMeta> = if config.infer_relations {
+        let known_foreign_keys: Vec<ForeignKeyMeta> = [foreign_keys.clone(), virtual_foreign_keys.clone()].concat();
+
+        tables
+            .iter()
+            .map(|table: &TableDef| {
+                infer_table_fks(table, &tables, &known_foreign_keys)
+                    .iter()
+                    .map(|fk: &TableForeignKey| parse_table_fk(table, fk, &table_create_stmt_map, config))
+                    .collect::<Result<Vec<ForeignKeyMeta>>>()
+            })
+            .collect::<Result<Vec<Vec<ForeignKeyMeta>>>>()?
+            .concat()
+    } else {
+        vec![]
+    };
+
+    // declared relations take part in the disambiguation, their names are applied on top
+    let foreign_keys: Vec<ForeignKeyMeta> = name_foreign_key_relations([foreign_keys, inferred_foreign_keys.clone(), virtual_foreign_keys].concat(), config);
+    let foreign_keys: Vec<ForeignKeyMeta> = name_virtual_foreign_key_relations(foreign_keys, config);
+
+    let foreign_keys: Vec<ForeignKeyMeta> = rename_foreign_key_relations(foreign_keys, config);
+
+    let many_to_many: Vec<ManyToManyMeta> = tables
+        .iter()
+        .flat_map(|table: &TableDef| parse_table_many_to_many(table, &foreign_keys))
+        .collect();
+
+    let many_to_many: Vec<ManyToManyMeta> = name_many_to_many_relations(many_to_many, config);
+
+    let tables_meta = tables
         .iter()
         .map(|table: &TableDef| {
-            let table_create_stmt = table.write();
+            let table_create_stmt = get_table_create_stmt(&table_create_stmt_map, &table.name)?;
 
-            let entity_name = table.name.to_upper_camel_case();
+            let entity_name = get_entity_name(&table.name, config);
 
             let foreign_keys: Vec<ForeignKeyMeta> = foreign_keys
                 .clone()
@@ -58,9 +104,15 @@ pub async fn get_database_schema(
                 .filter(|fk: &ForeignKeyMeta| fk.destination_table_name.eq(&entity_name) || fk.source_table_name.eq(&entity_name))
                 .collect();
 
+            let many_to_many: Vec<ManyToManyMeta> = many_to_many
+                .clone()
+                .into_iter()
+                .filter(|relation: &ManyToManyMeta| relation.is_source(&entity_name))
+                .collect();
+
             let columns: Vec<ColumnMeta> = table_create_stmt
                 .get_columns()
-                .into_iter()
+                .iter()
                 .map(|column: &ColumnDef| {
                     let column_name = column.get_column_name();
                     let column_spec: &Vec<ColumnSpec> = column.get_column_spec();
@@ -69,99 +121,219 @@ pub async fn get_database_schema(
                     let not_null = column_spec
                         .iter()
                         .any(|spec| matches!(spec, ColumnSpec::NotNull));
-                    let is_primary_key = column_spec
+                    // composite primary keys are not part of the column spec
+                    let is_primary_key = table
+                        .columns
                         .iter()
-                        .any(|spec| matches!(spec, ColumnSpec::PrimaryKey));
+                        .any(|column_info| column_info.name.eq(&column_name) && column_info.primary_key);
+                    let is_foreign_key = foreign_keys
+                        .iter()
+                        .any(|fk: &ForeignKeyMeta| fk.source_table_name.eq(&entity_name) && fk.source_columns.contains(&column_name));
+
+                    let field_name = config
+                        .get_column(&table.name, &column_name)
+                        .and_then(|column_config: &ColumnConfig| column_config.name.clone())
+                        .unwrap_or_else(|| column_name.to_snake_case());
 
-                    ColumnMeta {
+                    let column_filter_type: proc_macro2::TokenTree = column_info
+                        .get_rs_type() // TODO common function
+                        .into_iter()
+                        .find(|token: &proc_macro2::TokenTree| {
+                            if let proc_macro2::TokenTree::Ident(ident) = token {
+                                !ident.eq("Option")
+                            } else {
+                                false
+                            }
+                        })
+                        .ok_or_else(|| GeneratorError::UnsupportedColumnType {
+                            table: table.name.clone(),
+                            column: column_name.clone(),
+                            column_type: column_info.get_rs_type().to_string(),
+                        })?;
+
+                    Ok(ColumnMeta {
                         column_name: column_name.to_snake_case(),
+                        database_name: column_name.clone(),
+                        field_name,
                         column_enum_name: column_name.to_upper_camel_case(),
                         not_null,
                         column_type: column_info.get_rs_type(),
-                        column_filter_type: column_info
-                            .get_rs_type() // TODO common function
-                            .into_iter()
-                            .find(|token: &proc_macro2::TokenTree| {
-                                if let proc_macro2::TokenTree::Ident(ident) = token {
-                                    if ident.eq("Option") {
-                                        false
-                                    } else {
-                                        true
-                                    }
-                                } else {
-                                    false
-                                }
-                            })
-                            .unwrap()
-                            .into(),
+                        column_filter_type: column_filter_type.into(),
                         is_primary_key,
-                        is_foreign_key: false, // TODO
-                    }
+                        is_foreign_key,
+                    })
                 })
-                .collect();
+                .collect::<Result<Vec<ColumnMeta>>>()?;
 
-            TableMeta {
+            Ok(TableMeta {
+                table_name: table.name.clone(),
+                filter_name: config.naming.filter.replace("{}", &entity_name),
+                paginated_result_name: config.naming.paginated_result.replace("{}", &entity_name),
+                filter_template: config.naming.filter.clone(),
+                paginated_result_template: config.naming.paginated_result.clone(),
                 entity_name,
                 entity_module: table.name.to_snake_case(),
                 columns,
                 foreign_keys,
-            }
+                many_to_many,
+            })
         })
-        .collect();
+        .collect::<Result<Vec<TableMeta>>>()?;
+
+    let tables_create_stmts: Vec<TableCreateStatement> = tables
+        .iter()
+        .map(|table: &TableDef| get_table_create_stmt(&table_create_stmt_map, &table.name).cloned())
+        .collect::<Result<Vec<TableCreateStatement>>>()?;
+
+    Ok((tables_meta, tables_create_stmts, inferred_foreign_keys))
+}
+
+fn get_table_create_stmt<'a>(
+    table_create_stmt_map: &'a std::collections::HashMap<String, TableCreateStatement>,
+    table_name: &str,
+) -> Result<&'a TableCreateStatement> {
+    table_create_stmt_map
+        .get(table_name)
+        .ok_or_else(|| GeneratorError::MissingTable(table_name.into()))
+}
+
+fn get_column<'a>(table_create_stmt: &'a TableCreateStatement, table_name: &str, column_name: &String) -> Result<&'a ColumnDef> {
+    table_create_stmt
+        .get_columns()
+        .iter()
+        .find(|column: &&ColumnDef| column.get_column_name().eq(column_name))
+        .ok_or_else(|| GeneratorError::MissingColumn {
+            table: table_name.into(),
+            column: column_name.clone(),
+        })
+}
+
+fn get_entity_name(table_name: &str, config: &GeneratorConfig) -> String {
+    config
+        .get_table(table_name)
+        .and_then(|table_config: &TableConfig| table_config.name.clone())
+        .unwrap_or_else(|| table_name.to_upper_camel_case())
+}
+
+// relations only know the module, which is the snake_case database table name
+fn get_table_config_by_module<'a>(module: &str, config: &'a GeneratorConfig) -> Option<&'a TableConfig> {
+    config
+        .tables
+        .iter()
+        .find(|(table_name, _)| table_name.to_snake_case().eq(module))
+        .map(|(_, table_config)| table_config)
+}
+
+fn apply_table_config(table: &TableDef, config: &GeneratorConfig) -> Result<TableDef> {
+    let mut table = table.clone();
+
+    let table_config: TableConfig = config.get_table(&table.name).cloned().unwrap_or_default();
+
+    table
+        .columns
+        .retain(|column_info: &ColumnInfo| !table_config.exclude_columns.contains(&column_info.name));
+
+    for column_info in table.columns.iter_mut() {
+        if let Some(column_type) = table_config
+            .columns
+            .get(&column_info.name)
+            .and_then(|column_config: &ColumnConfig| column_config.column_type.as_ref())
+        {
+            column_info.r#type = Type::to_type(column_type).map_err(|_| GeneratorError::UnsupportedColumnType {
+                table: table.name.clone(),
+                column: column_info.name.clone(),
+                column_type: column_type.clone(),
+            })?;
+        }
+    }
+
+    Ok(table)
+}
+
+// foreign keys to excluded tables or columns are dropped so the ORM entities still compile
+fn write_table(table: &TableDef, tables: &[TableDef]) -> TableCreateStatement {
+    let table_create_stmt = table.write();
+
+    let has_columns = |table_name: &String, columns: &Vec<String>| -> bool {
+        tables
+            .iter()
+            .find(|table: &&TableDef| table.name.eq(table_name))
+            .map(|table: &TableDef| {
+                columns
+                    .iter()
+                    .all(|column: &String| table.columns.iter().any(|column_info: &ColumnInfo| column_info.name.eq(column)))
+            })
+            .unwrap_or(false)
+    };
+
+    let mut stmt = Table::create();
+    stmt.table(Alias::new(&table.name));
+
+    table_create_stmt.get_columns().iter().for_each(|column: &ColumnDef| {
+        stmt.col(&mut column.clone());
+    });
 
-    let tables_create_stmts: Vec<TableCreateStatement> =
-        schema.tables.iter().map(|table| table.write()).collect();
+    table_create_stmt
+        .get_foreign_key_create_stmts()
+        .iter()
+        .filter(|fk: &&ForeignKeyCreateStatement| {
+            let fk = fk.get_foreign_key();
+
+            has_columns(&table.name, &fk.get_columns())
+                && fk
+                    .get_ref_table()
+                    .map(|ref_table: String| has_columns(&ref_table, &fk.get_ref_columns()))
+                    .unwrap_or(false)
+        })
+        .for_each(|fk: &ForeignKeyCreateStatement| {
+            stmt.foreign_key(&mut fk.clone());
+        });
+
+    table_create_stmt.get_indexes().iter().for_each(|index| {
+        stmt.index(&mut index.clone());
+    });
 
-    (tables_meta, tables_create_stmts)
+    stmt
 }
 
 fn parse_table_fk(
     table: &TableDef,
     fk: &TableForeignKey,
     table_create_stmt_map: &std::collections::HashMap<String, TableCreateStatement>,
-) -> ForeignKeyMeta {
-    let source_table_name = table.name.clone().to_upper_camel_case();
-    let source_table_module = source_table_name.to_snake_case();
+    config: &GeneratorConfig,
+) -> Result<ForeignKeyMeta> {
+    let source_table_name = get_entity_name(&table.name, config);
+    let source_table_module = table.name.to_snake_case();
 
-    let source_table_create_stmt = table_create_stmt_map.get(&source_table_module).unwrap();
+    let source_table_create_stmt = get_table_create_stmt(table_create_stmt_map, &table.name)?;
 
     let source_column_types: Vec<_> = fk
         .get_columns()
         .iter()
-        .map(|name| {
-            Column::from(
-                source_table_create_stmt
-                    .get_columns()
-                    .into_iter()
-                    .find(|column: &&ColumnDef| column.get_column_name().eq(name))
-                    .unwrap(),
-            )
-        })
-        .map(|column_info: Column| column_info.get_rs_type())
-        .collect();
+        .map(|name| get_column(source_table_create_stmt, &table.name, name))
+        .map(|column| column.map(|column: &ColumnDef| Column::from(column).get_rs_type()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let destination_table = fk.get_ref_table().unwrap_or_default();
 
-    let destination_table_name = fk.get_ref_table().unwrap().to_upper_camel_case();
-    let destination_table_module = destination_table_name.to_snake_case();
+    let destination_table_name = get_entity_name(&destination_table, config);
+    let destination_table_module = destination_table.to_snake_case();
 
-    let destination_table_create_stmt: &TableCreateStatement = table_create_stmt_map.get(&destination_table_module).unwrap();
+    let destination_table_create_stmt: &TableCreateStatement = get_table_create_stmt(table_create_stmt_map, &destination_table)?;
 
     let destination_column_types: Vec<_> = fk
         .get_ref_columns()
         .iter()
-        .map(|name| {
-            Column::from(
-                destination_table_create_stmt
-                    .get_columns()
-                    .into_iter()
-                    .find(|column: &&ColumnDef| column.get_column_name().eq(name))
-                    .unwrap(),
-            )
-        })
-        .map(|column_info: Column| column_info.get_rs_type())
-        .collect();
+        .map(|name| get_column(destination_table_create_stmt, &destination_table, name))
+        .map(|column| column.map(|column: &ColumnDef| Column::from(column).get_rs_type()))
+        .collect::<Result<Vec<_>>>()?;
+
 
+    let strip_id_suffix = config.naming.strip_id_suffix;
+    let relation_name = to_one_relation_name(&relation_prefix(&fk.get_columns(), strip_id_suffix), &destination_table_module, table, config);
+    let reverse_relation_name = format!("{}_{}", relation_prefix(&fk.get_ref_columns(), strip_id_suffix), source_table_module);
 
-    ForeignKeyMeta {
+    Ok(ForeignKeyMeta {
         source_table_name,
         source_table_module,
         source_columns: fk.get_columns(),
@@ -172,5 +344,551 @@ fn parse_table_fk(
         destination_columns: fk.get_ref_columns(),
         destination_column_types,
 
+        relation_name,
+        reverse_relation_name,
+
+        one_to_one: false,
+
+        declared_relation: None,
+    })
+}
+
+fn parse_virtual_fk(
+    index: usize,
+    relation: &RelationConfig,
+    tables: &[TableDef],
+    table_create_stmt_map: &std::collections::HashMap<String, TableCreateStatement>,
+    config: &GeneratorConfig,
+) -> Result<ForeignKeyMeta> {
+    if relation.source_columns.is_empty() || relation.source_columns.len() != relation.destination_columns.len() {
+        return Err(GeneratorError::InvalidConfig(format!(
+            "relation `{}` needs the same, non-zero number of source and destination columns",
+            relation.describe()
+        )));
+    }
+
+    let table: &TableDef = tables
+        .iter()
+        .find(|table: &&TableDef| table.name.eq(&relation.source_table))
+        .ok_or_else(|| GeneratorError::MissingTable(relation.source_table.clone()))?;
+
+    get_table_create_stmt(table_create_stmt_map, &relation.destination_table)?;
+
+    let mut fk: TableForeignKey = TableForeignKey::new();
+    fk.from_tbl(Alias::new(&relation.source_table))
+        .to_tbl(Alias::new(&relation.destination_table));
+    relation.source_columns.iter().for_each(|column: &String| {
+        fk.from_col(Alias::new(column));
+    });
+    relation.destination_columns.iter().for_each(|column: &String| {
+        fk.to_col(Alias::new(column));
+    });
+
+    let mut fk: ForeignKeyMeta = parse_table_fk(table, &fk, table_create_stmt_map, config)?;
+
+    fk.one_to_one = relation.cardinality == Cardinality::OneToOne;
+    fk.declared_relation = Some(index);
+
+    // the reverse side resolves to a single model as well
+    if fk.one_to_one {
+        let destination_table: &TableDef = tables
+            .iter()
+            .find(|table: &&TableDef| table.name.eq(&relation.destination_table))
+            .ok_or_else(|| GeneratorError::MissingTable(relation.destination_table.clone()))?;
+
+        fk.reverse_relation_name = to_one_relation_name(
+            &relation_prefix(&relation.destination_columns, config.naming.strip_id_suffix),
+            &fk.source_table_module,
+            destination_table,
+            config,
+        );
+    }
+
+    Ok(fk)
+}
+
+// a declared relation on the columns of a foreign key would generate the same loader key twice
+fn check_virtual_fks(virtual_foreign_keys: &[ForeignKeyMeta], foreign_keys: &[ForeignKeyMeta], config: &GeneratorConfig) -> Result<()> {
+    for (position, fk) in virtual_foreign_keys.iter().enumerate() {
+        let duplicate = foreign_keys
+            .iter()
+            .chain(virtual_foreign_keys[..position].iter())
+            .any(|other: &ForeignKeyMeta| fk.has_source_columns_of(other));
+
+        if duplicate {
+            let relation: &RelationConfig = &config.relations[position];
+
+            return Err(GeneratorError::InvalidConfig(format!(
+                "relation `{}` is declared on the columns of another relation of `{}`",
+                relation.describe(),
+                relation.source_table
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn infer_table_fks(
+    table: &TableDef,
+    tables: &[TableDef],
+    foreign_keys: &[ForeignKeyMeta],
+) -> Vec<TableForeignKey> {
+    let source_table_module = table.name.to_snake_case();
+
+    table
+        .columns
+        .iter()
+        .filter(|column_info| {
+            !foreign_keys
+                .iter()
+                .any(|fk: &ForeignKeyMeta| fk.source_table_module.eq(&source_table_module) && fk.source_columns.contains(&column_info.name))
+        })
+        .filter_map(|column_info| {
+            let column_name = column_info.name.to_snake_case();
+
+            let referenced_name = column_name.strip_suffix("_id")?;
+
+            let referenced_table: &TableDef = tables
+                .iter()
+                .find(|candidate: &&TableDef| is_table_named(&candidate.name.to_snake_case(), referenced_name))?;
+
+            let referenced_primary_keys: Vec<&String> = referenced_table
+                .columns
+                .iter()
+                .filter(|candidate_column| candidate_column.primary_key)
+                .map(|candidate_column| &candidate_column.name)
+                .collect();
+
+            // only single column keys can be matched by name, a table does not reference its own key
+            if referenced_primary_keys.len() != 1 || (referenced_table.name.eq(&table.name) && column_info.primary_key) {
+                return None;
+            }
+
+            Some(
+                ForeignKey::create()
+                    .from(Alias::new(&table.name), Alias::new(&column_info.name))
+                    .to(Alias::new(&referenced_table.name), Alias::new(referenced_primary_keys[0]))
+                    .get_foreign_key()
+                    .clone()
+            )
+        })
+        .collect()
+}
+
+// table names are commonly the plural of the referencing column, e.g. `album_id` -> `albums`
+fn is_table_named(table_module: &str, name: &str) -> bool {
+    let plural = match name.strip_suffix('y') {
+        Some(stem) => format!("{}ies", stem),
+        None => format!("{}s", name),
+    };
+
+    table_module.eq(name)
+        || table_module.eq(&plural)
+        || table_module.eq(&format!("{}es", name))
+}
+
+// column names without the `_id` suffix, e.g. ["ReportsTo"] -> "reports_to", ["ArtistId"] -> "artist"
+fn relation_prefix(columns: &[String], strip_id_suffix: bool) -> String {
+    columns
+        .iter()
+        .map(|s: &String| s.to_snake_case())
+        .map(|s: String| {
+            if strip_id_suffix && s.ends_with("_id") {
+                String::from(s.split_at(s.len() - 3).0)
+            } else {
+                s
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+// a single model is named after the singular table: `reports_to_employee`, or just `artist` when
+// the columns already name the table, unless the table has a field of that name
+fn to_one_relation_name(prefix: &str, table_module: &str, table: &TableDef, config: &GeneratorConfig) -> String {
+    let singular = singular_name(table_module);
+
+    let has_field = table.columns.iter().any(|column_info: &ColumnInfo| {
+        config
+            .get_column(&table.name, &column_info.name)
+            .and_then(|column_config: &ColumnConfig| column_config.name.clone())
+            .unwrap_or_else(|| column_info.name.to_snake_case())
+            .eq(&singular)
+    });
+
+    if prefix.eq(&singular) && !has_field {
+        singular
+    } else {
+        format!("{}_{}", prefix, singular)
+    }
+}
+
+// undoes the plurals `is_table_named` accepts, e.g. `categories` -> `category`, `media_types` -> `media_type`
+fn singular_name(table_module: &str) -> String {
+    if let Some(stem) = table_module.strip_suffix("ies") {
+        return format!("{}y", stem);
+    }
+
+    if ["sses", "xes", "ches", "shes"].iter().any(|suffix: &&str| table_module.ends_with(suffix)) {
+        return table_module[..table_module.len() - 2].into();
+    }
+
+    match table_module.strip_suffix('s') {
+        Some(stem) if !stem.ends_with('s') => stem.into(),
+        _ => table_module.into(),
+    }
+}
+
+// reverse names are built from the referenced columns, so they clash when the same
+// table is referenced more than once from one table or references itself
+// domain names (`subordinates` for `employees_by_reports_to`) are not derived, they come from `[tables.<table>.relations]`
+fn name_foreign_key_relations(foreign_keys: Vec<ForeignKeyMeta>, config: &GeneratorConfig) -> Vec<ForeignKeyMeta> {
+    foreign_keys
+        .iter()
+        .map(|fk: &ForeignKeyMeta| {
+            let ambiguous = fk.is_self_reference() || foreign_keys
+                .iter()
+                .filter(|other: &&ForeignKeyMeta| {
+                    other.source_table_name.eq(&fk.source_table_name)
+                        && other.destination_table_name.eq(&fk.destination_table_name)
+                })
+                .count() > 1;
+
+            let mut fk = fk.clone();
+
+            if ambiguous {
+                let source_name = if fk.one_to_one { singular_name(&fk.source_table_module) } else { fk.source_table_module.clone() };
+
+                fk.reverse_relation_name = format!("{}_by_{}", source_name, relation_prefix(&fk.source_columns, config.naming.strip_id_suffix));
+            }
+
+            fk
+        })
+        .collect()
+}
+
+// names of `[[relations]]`, found through the index the declared keys carry
+fn name_virtual_foreign_key_relations(foreign_keys: Vec<ForeignKeyMeta>, config: &GeneratorConfig) -> Vec<ForeignKeyMeta> {
+    foreign_keys
+        .into_iter()
+        .map(|mut fk: ForeignKeyMeta| {
+            if let Some(relation) = fk.declared_relation.and_then(|index: usize| config.relations.get(index)) {
+                if let Some(name) = &relation.name {
+                    fk.relation_name = name.clone();
+                }
+
+                if let Some(reverse_name) = &relation.reverse_name {
+                    fk.reverse_relation_name = reverse_name.clone();
+                }
+            }
+
+            fk
+        })
+        .collect()
+}
+
+// `[tables.<table>.relations]` renames, applied on top of generated and declared names
+fn rename_foreign_key_relations(foreign_keys: Vec<ForeignKeyMeta>, config: &GeneratorConfig) -> Vec<ForeignKeyMeta> {
+    foreign_keys
+        .into_iter()
+        .map(|mut fk: ForeignKeyMeta| {
+            if let Some(name) = get_table_config_by_module(&fk.source_table_module, config)
+                .and_then(|table_config: &TableConfig| table_config.relations.get(&fk.relation_name))
+            {
+                fk.relation_name = name.clone();
+            }
+
+            if let Some(name) = get_table_config_by_module(&fk.destination_table_module, config)
+                .and_then(|table_config: &TableConfig| table_config.relations.get(&fk.reverse_relation_name))
+            {
+                fk.reverse_relation_name = name.clone();
+            }
+
+            fk
+        })
+        .collect()
+}
+
+fn parse_table_many_to_many(
+    table: &TableDef,
+    foreign_keys: &[ForeignKeyMeta],
+) -> Vec<ManyToManyMeta> {
+    let junction_table_module = table.name.to_snake_case();
+    let junction_table_name = junction_table_module.to_upper_camel_case();
+
+    let mut primary_key_columns: Vec<String> = table
+        .columns
+        .iter()
+        .filter(|column_info| column_info.primary_key)
+        .map(|column_info| column_info.name.clone())
+        .collect();
+    primary_key_columns.sort();
+
+    let junction_foreign_keys: Vec<&ForeignKeyMeta> = foreign_keys
+        .iter()
+        .filter(|fk: &&ForeignKeyMeta| fk.source_table_module.eq(&junction_table_module))
+        .collect();
+
+    if junction_foreign_keys.len() != 2 {
+        return vec![];
+    }
+
+    let mut foreign_key_columns: Vec<String> = junction_foreign_keys
+        .iter()
+        .flat_map(|fk: &&ForeignKeyMeta| fk.source_columns.clone())
+        .collect();
+    foreign_key_columns.sort();
+
+    if !primary_key_columns.eq(&foreign_key_columns) {
+        return vec![];
+    }
+
+    let first = junction_foreign_keys[0];
+    let second = junction_foreign_keys[1];
+
+    vec![
+        ManyToManyMeta {
+            junction_table_name: junction_table_name.clone(),
+            junction_table_module: junction_table_module.clone(),
+            source_foreign_key: first.clone(),
+            destination_foreign_key: second.clone(),
+            relation_name: second.destination_table_module.clone(),
+        },
+        ManyToManyMeta {
+            junction_table_name,
+            junction_table_module,
+            source_foreign_key: second.clone(),
+            destination_foreign_key: first.clone(),
+            relation_name: first.destination_table_module.clone(),
+        },
+    ]
+}
+
+// a table linked to the same table through several junctions (or to itself) gets
+// the junction column in the relation name, e.g. `friend_users`
+fn name_many_to_many_relations(many_to_many: Vec<ManyToManyMeta>, config: &GeneratorConfig) -> Vec<ManyToManyMeta> {
+    many_to_many
+        .iter()
+        .map(|relation: &ManyToManyMeta| {
+            let ambiguous = many_to_many
+                .iter()
+                .filter(|other: &&ManyToManyMeta| {
+                    other.source_foreign_key.destination_table_name.eq(&relation.source_foreign_key.destination_table_name)
+                        && other.relation_name.eq(&relation.relation_name)
+                })
+                .count() > 1;
+
+            let mut relation = relation.clone();
+
+            if ambiguous {
+                relation.relation_name = format!(
+                    "{}_{}",
+                    relation_prefix(&relation.destination_foreign_key.source_columns, config.naming.strip_id_suffix),
+                    relation.destination_foreign_key.destination_table_module
+                );
+            }
+
+            if let Some(name) = get_table_config_by_module(&relation.source_foreign_key.destination_table_module, config)
+                .and_then(|table_config: &TableConfig| table_config.relations.get(&relation.relation_name))
+            {
+                relation.relation_name = name.clone();
+            }
+
+            relation
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use sea_schema::sqlite::def::DefaultType;
+
+    fn table(name: &str, columns: &[(&str, bool)]) -> TableDef {
+        TableDef {
+            name: name.into(),
+            columns: columns
+                .iter()
+                .enumerate()
+                .map(|(cid, (column_name, primary_key))| ColumnInfo {
+                    cid: cid as i32,
+                    name: (*column_name).into(),
+                    r#type: Type::Integer,
+                    not_null: true,
+                    default_value: DefaultType::Unspecified,
+                    primary_key: *primary_key,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    // named like `parse_table_fk` names them
+    fn foreign_key(source_table: &str, source_columns: &[&str], destination_table: &str) -> ForeignKeyMeta {
+        let source_columns: Vec<String> = source_columns.iter().map(|column| (*column).into()).collect();
+
+        ForeignKeyMeta {
+            source_table_name: source_table.to_upper_camel_case(),
+            source_table_module: source_table.into(),
+            source_column_types: source_columns.iter().map(|_| quote!(i32)).collect(),
+            relation_name: relation_prefix(&source_columns, true),
+            source_columns,
+
+            destination_table_name: destination_table.to_upper_camel_case(),
+            destination_table_module: destination_table.into(),
+            destination_columns: vec!["Id".into()],
+            destination_column_types: vec![quote!(i32)],
+
+            reverse_relation_name: format!("id_{}", source_table),
+
+            one_to_one: false,
+
+            declared_relation: None,
+        }
+    }
+
+    #[test]
+    fn tables_are_named_after_columns() {
+        assert!(is_table_named("albums", "album"));
+        assert!(is_table_named("categories", "category"));
+        assert!(is_table_named("addresses", "address"));
+        assert!(is_table_named("media", "media"));
+        assert!(!is_table_named("albums", "artist"));
+    }
+
+    #[test]
+    fn singular_names_undo_plurals() {
+        assert_eq!(singular_name("albums"), "album");
+        assert_eq!(singular_name("media_types"), "media_type");
+        assert_eq!(singular_name("categories"), "category");
+        assert_eq!(singular_name("addresses"), "address");
+        assert_eq!(singular_name("boxes"), "box");
+        assert_eq!(singular_name("branches"), "branch");
+        assert_eq!(singular_name("status"), "statu");
+        assert_eq!(singular_name("class"), "class");
+    }
+
+    #[test]
+    fn relation_prefixes_drop_the_id_suffix() {
+        assert_eq!(relation_prefix(&["ArtistId".into()], true), "artist");
+        assert_eq!(relation_prefix(&["ArtistId".into()], false), "artist_id");
+        assert_eq!(relation_prefix(&["ReportsTo".into()], true), "reports_to");
+        assert_eq!(relation_prefix(&["InvoiceId".into(), "LineId".into()], true), "invoice_line");
+    }
+
+    #[test]
+    fn to_one_relations_are_named_after_the_singular_table() {
+        let config = GeneratorConfig::default();
+        let albums = table("albums", &[("AlbumId", true), ("ArtistId", false)]);
+        let customers = table("customers", &[("CustomerId", true), ("SupportRepId", false)]);
+
+        assert_eq!(to_one_relation_name("artist", "artists", &albums, &config), "artist");
+        assert_eq!(to_one_relation_name("support_rep", "employees", &customers, &config), "support_rep_employee");
+    }
+
+    #[test]
+    fn to_one_relations_do_not_shadow_fields() {
+        let tracks = table("tracks", &[("TrackId", true), ("Genre", false)]);
+
+        let mut config = GeneratorConfig::default();
+        assert_eq!(to_one_relation_name("genre", "genres", &tracks, &config), "genre_genre");
+
+        // a renamed column frees the name
+        config.tables.insert(
+            "tracks".into(),
+            TableConfig {
+                columns: [("Genre".to_string(), ColumnConfig { name: Some("genre_name".into()), column_type: None })].into(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(to_one_relation_name("genre", "genres", &tracks, &config), "genre");
+    }
+
+    #[test]
+    fn ambiguous_reverse_relations_are_named_by_columns() {
+        let config = GeneratorConfig::default();
+
+        let foreign_keys = name_foreign_key_relations(
+            vec![
+                foreign_key("albums", &["ArtistId"], "artists"),
+                foreign_key("employees", &["ReportsTo"], "employees"),
+                foreign_key("invoices", &["BillingCustomerId"], "customers"),
+                foreign_key("invoices", &["ShippingCustomerId"], "customers"),
+            ],
+            &config,
+        );
+
+        let reverse_names: Vec<&str> = foreign_keys.iter().map(|fk| fk.reverse_relation_name.as_str()).collect();
+
+        assert_eq!(
+            reverse_names,
+            vec!["id_albums", "employees_by_reports_to", "invoices_by_billing_customer", "invoices_by_shipping_customer"]
+        );
+    }
+
+    #[test]
+    fn one_to_one_reverse_relations_are_singular() {
+        let mut fk = foreign_key("employees", &["MentorId"], "employees");
+        fk.one_to_one = true;
+
+        let foreign_keys = name_foreign_key_relations(vec![fk], &GeneratorConfig::default());
+
+        assert_eq!(foreign_keys[0].reverse_relation_name, "employee_by_mentor");
+    }
+
+    #[test]
+    fn junction_tables_are_many_to_many() {
+        let playlist_track = table("playlist_track", &[("PlaylistId", true), ("TrackId", true)]);
+        let foreign_keys = vec![
+            foreign_key("playlist_track", &["PlaylistId"], "playlists"),
+            foreign_key("playlist_track", &["TrackId"], "tracks"),
+        ];
+
+        let many_to_many = parse_table_many_to_many(&playlist_track, &foreign_keys);
+        let relations: Vec<(&str, &str)> = many_to_many
+            .iter()
+            .map(|relation| (relation.source_foreign_key.destination_table_module.as_str(), relation.relation_name.as_str()))
+            .collect();
+
+        assert_eq!(relations, vec![("playlists", "tracks"), ("tracks", "playlists")]);
+        assert_eq!(many_to_many[0].junction_table_name, "PlaylistTrack");
+    }
+
+    #[test]
+    fn junction_tables_need_a_key_made_of_both_foreign_keys() {
+        let foreign_keys = vec![
+            foreign_key("playlist_track", &["PlaylistId"], "playlists"),
+            foreign_key("playlist_track", &["TrackId"], "tracks"),
+        ];
+
+        let own_key = table("playlist_track", &[("Id", true), ("PlaylistId", false), ("TrackId", false)]);
+        assert!(parse_table_many_to_many(&own_key, &foreign_keys).is_empty());
+
+        let one_key = table("playlist_track", &[("PlaylistId", true), ("TrackId", true)]);
+        assert!(parse_table_many_to_many(&one_key, &foreign_keys[..1]).is_empty());
+    }
+
+    #[test]
+    fn declared_relations_on_existing_keys_are_rejected() {
+        let mut config = GeneratorConfig::default();
+        config.relations.push(RelationConfig {
+            source_table: "albums".into(),
+            source_columns: vec!["ArtistId".into()],
+            destination_table: "artists".into(),
+            destination_columns: vec!["ArtistId".into()],
+            cardinality: Cardinality::ManyToOne,
+            name: None,
+            reverse_name: None,
+        });
+
+        let declared = vec![foreign_key("albums", &["ArtistId"], "artists")];
+
+        assert!(check_virtual_fks(&declared, &[], &config).is_ok());
+        assert!(matches!(
+            check_virtual_fks(&declared, &declared, &config),
+            Err(GeneratorError::InvalidConfig(_))
+        ));
     }
 }
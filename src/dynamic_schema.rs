@@ -0,0 +1,800 @@
+use crate::{
+    error::{GeneratorError, Result},
+    graphql::entities::{get_hierarchy_relation_names, get_hierarchy_sql, get_relation_directions},
+    scalar_mapping::{get_scalar, FILTERS, SCALARS},
+    types::{ColumnMeta, ForeignKeyMeta, ManyToManyMeta, TableMeta},
+};
+use async_graphql::{
+    dataloader::{DataLoader, Loader},
+    dynamic::{Field, FieldFuture, FieldValue, InputObject, InputValue, Object, ResolverContext, Scalar, Schema, TypeRef},
+    http::{playground_source, GraphQLPlaygroundConfig},
+    ErrorExtensions, Value,
+};
+use heck::ToLowerCamelCase;
+use poem::{
+    get, handler,
+    listener::TcpListener,
+    web::{Data, Html, Json},
+    EndpointExt, IntoResponse, Route, Server,
+};
+use sqlx::{
+    sqlite::{SqliteArguments, SqliteRow},
+    Arguments, Column, Row, SqlitePool, TypeInfo, ValueRef,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+// the same codes as the generated `graphql::errors`
+pub const NOT_FOUND: &str = "NOT_FOUND";
+pub const DATABASE_ERROR: &str = "DATABASE_ERROR";
+pub const INVALID_FILTER: &str = "INVALID_FILTER";
+
+// a row keyed by the column names of the database
+#[derive(Clone, Debug)]
+struct DynamicRow(BTreeMap<String, SqlValue>);
+
+#[derive(Clone, Debug)]
+enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+// what the filter of a column needs, `TableMeta` is not `Send`
+#[derive(Clone)]
+struct FilterColumn {
+    field_name: String, // camelCase
+    database_name: String,
+    scalar: &'static str,
+}
+
+impl PartialEq for SqlValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SqlValue::Null, SqlValue::Null) => true,
+            (SqlValue::Integer(value), SqlValue::Integer(other)) => value.eq(other),
+            (SqlValue::Real(value), SqlValue::Real(other)) => value.to_bits().eq(&other.to_bits()),
+            (SqlValue::Text(value), SqlValue::Text(other)) => value.eq(other),
+            (SqlValue::Blob(value), SqlValue::Blob(other)) => value.eq(other),
+            _ => false,
+        }
+    }
+}
+
+// keys of the relation loader, reals are compared by their bits
+impl Eq for SqlValue {}
+
+impl Hash for SqlValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+
+        match self {
+            SqlValue::Null => {}
+            SqlValue::Integer(value) => value.hash(state),
+            SqlValue::Real(value) => value.to_bits().hash(state),
+            SqlValue::Text(value) => value.hash(state),
+            SqlValue::Blob(value) => value.hash(state),
+        }
+    }
+}
+
+struct PaginatedRows {
+    data: Vec<DynamicRow>,
+    pages: u64,
+    current: u64,
+}
+
+// `SELECT` of the related rows, the columns compared with the keys are selected again as `__key_<index>`
+struct RelationQuery {
+    select: String,
+    key_columns: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct RelationKey {
+    relation: usize, // index in `RelationLoader::relations`
+    values: Vec<SqlValue>,
+}
+
+// like the `OrmDataLoader` of the generated project, the keys of a page are loaded with one query per relation
+struct RelationLoader {
+    connection: SqlitePool,
+    relations: Vec<RelationQuery>,
+}
+
+impl Loader<RelationKey> for RelationLoader {
+    type Value = Vec<DynamicRow>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[RelationKey]) -> async_graphql::Result<HashMap<RelationKey, Self::Value>> {
+        let mut keys_by_relation: BTreeMap<usize, Vec<&RelationKey>> = BTreeMap::new();
+
+        for key in keys.iter() {
+            keys_by_relation.entry(key.relation).or_default().push(key);
+        }
+
+        let mut loaded: HashMap<RelationKey, Self::Value> = HashMap::new();
+
+        for (relation, keys) in keys_by_relation.into_iter() {
+            let query = &self.relations[relation];
+
+            let row_placeholders = format!("({})", vec!["?"; query.key_columns.len()].join(", "));
+            let sql = format!(
+                "{} WHERE ({}) IN (VALUES {})",
+                query.select,
+                query.key_columns.join(", "),
+                vec![row_placeholders; keys.len()].join(", ")
+            );
+            let values: Vec<SqlValue> = keys.iter().flat_map(|key| key.values.iter().cloned()).collect();
+
+            for mut row in fetch_rows(&self.connection, &sql, values).await?.into_iter() {
+                let values: Vec<SqlValue> = (0..query.key_columns.len())
+                    .map(|index| row.0.remove(&format!("__key_{}", index)).unwrap_or(SqlValue::Null))
+                    .collect();
+
+                loaded.entry(RelationKey { relation, values }).or_default().push(row);
+            }
+        }
+
+        Ok(loaded)
+    }
+}
+
+// the API of the generated project built at runtime, queries go straight to the database
+// `Schema::sdl` matches the schema of the generated project without its extensions
+pub fn build_dynamic_schema(tables_meta: &[TableMeta], connection: SqlitePool) -> Result<Schema> {
+    let mut query = Object::new("Query");
+
+    let mut builder = Schema::build("Query", None, None);
+
+    // like the generated schema, only the scalars and filters some column uses
+    let (scalars, filters): (BTreeSet<&str>, BTreeSet<Option<&str>>) = tables_meta
+        .iter()
+        .flat_map(|table: &TableMeta| table.columns.iter())
+        .map(|column: &ColumnMeta| get_scalar(&column.column_filter_type.to_string()))
+        .unzip();
+
+    for (filter_name, scalar) in FILTERS.iter().filter(|(filter_name, _)| filters.contains(&Some(*filter_name))) {
+        builder = builder.register(generate_type_filter(filter_name, scalar));
+    }
+
+    let custom_scalars = SCALARS
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| !["ID", "String", "Boolean", "Int", "Float"].contains(name))
+        .filter(|name| {
+            scalars.contains(name) || FILTERS.iter().any(|(filter_name, scalar)| scalar.eq(name) && filters.contains(&Some(*filter_name)))
+        });
+
+    for name in custom_scalars {
+        builder = builder.register(Scalar::new(name));
+    }
+
+    builder = builder.register(
+        InputObject::new("PaginationInput")
+            .field(InputValue::new("limit", TypeRef::named_nn(TypeRef::INT)))
+            .field(InputValue::new("page", TypeRef::named_nn(TypeRef::INT))),
+    );
+
+    let mut relations: Vec<RelationQuery> = vec![];
+
+    for table in tables_meta.iter() {
+        builder = builder
+            .register(generate_entity_object(table, tables_meta, &mut relations)?)
+            .register(generate_entity_filter(table))
+            .register(generate_paginated_result(table));
+
+        query = query.field(generate_root_field(table));
+    }
+
+    let loader = DataLoader::new(RelationLoader { connection: connection.clone(), relations }, tokio::spawn);
+
+    builder
+        .register(query)
+        .data(connection)
+        .data(loader)
+        .finish()
+        .map_err(|error| GeneratorError::DynamicSchema(error.to_string()))
+}
+
+// `cargo run serve [<address>]`, the playground is served on `GET /`
+pub async fn serve_dynamic_schema(schema: Schema, address: &str) -> Result<()> {
+    let app = Route::new()
+        .at("/", get(graphql_playground).post(graphql_handler))
+        .data(schema);
+
+    Server::new(TcpListener::bind(address)).run(app).await?;
+
+    Ok(())
+}
+
+#[handler]
+async fn graphql_handler(schema: Data<&Schema>, request: Json<async_graphql::Request>) -> Json<async_graphql::Response> {
+    Json(schema.execute(request.0).await)
+}
+
+#[handler]
+async fn graphql_playground() -> impl IntoResponse {
+    Html(playground_source(GraphQLPlaygroundConfig::new("/")))
+}
+
+fn generate_type_filter(filter_name: &str, scalar: &str) -> InputObject {
+    let mut filter = InputObject::new(filter_name);
+
+    for name in ["eq", "ne", "gt", "gte", "lt", "lte"] {
+        filter = filter.field(InputValue::new(name, TypeRef::named(scalar)));
+    }
+
+    filter
+        .field(InputValue::new("isIn", TypeRef::named_nn_list(scalar)))
+        .field(InputValue::new("isNotIn", TypeRef::named_nn_list(scalar)))
+        .field(InputValue::new("isNull", TypeRef::named(TypeRef::BOOLEAN)))
+}
+
+fn generate_entity_filter(table: &TableMeta) -> InputObject {
+    let mut filter = InputObject::new(&table.filter_name)
+        .field(InputValue::new("or", TypeRef::named_nn_list(&table.filter_name)))
+        .field(InputValue::new("and", TypeRef::named_nn_list(&table.filter_name)));
+
+    for column in table.columns.iter() {
+        if let (_, Some(filter_name)) = get_scalar(&column.column_filter_type.to_string()) {
+            filter = filter.field(InputValue::new(column.field_name.to_lower_camel_case(), TypeRef::named(filter_name)));
+        }
+    }
+
+    filter
+}
+
+fn generate_paginated_result(table: &TableMeta) -> Object {
+    Object::new(&table.paginated_result_name)
+        .field(Field::new("data", TypeRef::named_nn_list_nn(&table.entity_name), |ctx| {
+            FieldFuture::new(async move {
+                let result = ctx.parent_value.try_downcast_ref::<PaginatedRows>()?;
+
+                Ok(Some(FieldValue::list(result.data.iter().cloned().map(FieldValue::owned_any))))
+            })
+        }))
+        .field(Field::new("pages", TypeRef::named_nn(TypeRef::INT), |ctx| {
+            FieldFuture::new(async move {
+                let result = ctx.parent_value.try_downcast_ref::<PaginatedRows>()?;
+
+                Ok(Some(Value::from(result.pages)))
+            })
+        }))
+        .field(Field::new("current", TypeRef::named_nn(TypeRef::INT), |ctx| {
+            FieldFuture::new(async move {
+                let result = ctx.parent_value.try_downcast_ref::<PaginatedRows>()?;
+
+                Ok(Some(Value::from(result.current)))
+            })
+        }))
+}
+
+fn generate_root_field(table: &TableMeta) -> Field {
+    let columns: Arc<Vec<FilterColumn>> = Arc::new(
+        table
+            .columns
+            .iter()
+            .map(|column: &ColumnMeta| FilterColumn {
+                field_name: column.field_name.to_lower_camel_case(),
+                database_name: column.database_name.clone(),
+                scalar: get_scalar(&column.column_filter_type.to_string()).0,
+            })
+            .collect(),
+    );
+
+    let table_name = quote_name(&table.table_name);
+
+    Field::new(
+        table.entity_module.to_lower_camel_case(),
+        TypeRef::named_nn(&table.paginated_result_name),
+        move |ctx| {
+            let columns = columns.clone();
+            let table_name = table_name.clone();
+
+            FieldFuture::new(async move {
+                let connection = ctx.data::<SqlitePool>()?;
+
+                let mut values: Vec<SqlValue> = vec![];
+
+                let condition = match get_argument(&ctx, "filters") {
+                    Some(filters) => generate_condition(&columns, filters, &mut values)?,
+                    None => None,
+                }
+                .map(|condition| format!(" WHERE {}", condition))
+                .unwrap_or_default();
+
+                let result = match get_argument(&ctx, "pagination") {
+                    Some(pagination) => {
+                        let limit = get_pagination_value(pagination, "limit")?;
+                        let page = get_pagination_value(pagination, "page")?;
+
+                        if limit == 0 {
+                            return Err(error("pagination limit must be greater than 0", INVALID_FILTER));
+                        }
+
+                        let count: i64 = sqlx::query_scalar_with(
+                            &format!("SELECT COUNT(*) FROM {}{}", table_name, condition),
+                            get_arguments(values.clone()),
+                        )
+                        .fetch_one(connection)
+                        .await
+                        .map_err(|error| database_error(&error))?;
+
+                        values.push(SqlValue::Integer(limit as i64));
+                        values.push(SqlValue::Integer((limit * page) as i64));
+
+                        let data = fetch_rows(
+                            connection,
+                            &format!("SELECT * FROM {}{} LIMIT ? OFFSET ?", table_name, condition),
+                            values,
+                        )
+                        .await?;
+
+                        PaginatedRows {
+                            data,
+                            pages: (count as u64).div_ceil(limit),
+                            current: page,
+                        }
+                    }
+                    None => PaginatedRows {
+                        data: fetch_rows(connection, &format!("SELECT * FROM {}{}", table_name, condition), values).await?,
+                        pages: 1,
+                        current: 1,
+                    },
+                };
+
+                Ok(Some(FieldValue::owned_any(result)))
+            })
+        },
+    )
+    .argument(InputValue::new("filters", TypeRef::named(&table.filter_name)))
+    .argument(InputValue::new("pagination", TypeRef::named("PaginationInput")))
+}
+
+fn generate_entity_object(table: &TableMeta, tables: &[TableMeta], relations: &mut Vec<RelationQuery>) -> Result<Object> {
+    let mut object = Object::new(&table.entity_name);
+
+    for column in table.columns.iter() {
+        object = object.field(generate_column_field(column));
+    }
+
+    for (fk, reverse) in get_relation_directions(table).into_iter() {
+        object = object.field(generate_relation_field(fk, reverse, tables, relations)?);
+    }
+
+    for relation in table.many_to_many.iter() {
+        object = object.field(generate_many_to_many_field(relation, tables, relations)?);
+    }
+
+    for (fk, ancestors_name, descendants_name) in get_hierarchy_relation_names(table).into_iter() {
+        object = object
+            .field(generate_hierarchy_field(table, fk, &ancestors_name, true))
+            .field(generate_hierarchy_field(table, fk, &descendants_name, false));
+    }
+
+    Ok(object)
+}
+
+fn generate_column_field(column: &ColumnMeta) -> Field {
+    let (scalar, _) = get_scalar(&column.column_filter_type.to_string());
+
+    let field_type = if column.not_null { TypeRef::named_nn(scalar) } else { TypeRef::named(scalar) };
+
+    let database_name = column.database_name.clone();
+
+    Field::new(column.field_name.to_lower_camel_case(), field_type, move |ctx| {
+        let database_name = database_name.clone();
+
+        FieldFuture::new(async move {
+            let row = ctx.parent_value.try_downcast_ref::<DynamicRow>()?;
+
+            let value = row.0.get(&database_name).unwrap_or(&SqlValue::Null);
+
+            Ok(Some(to_graphql_value(value, scalar)))
+        })
+    })
+}
+
+fn generate_relation_field(fk: &ForeignKeyMeta, reverse: bool, tables: &[TableMeta], relations: &mut Vec<RelationQuery>) -> Result<Field> {
+    let source_columns = if reverse { fk.destination_columns.clone() } else { fk.source_columns.clone() };
+    let destination_columns = if reverse { &fk.source_columns } else { &fk.destination_columns };
+
+    let destination_entity = if reverse { &fk.source_table_name } else { &fk.destination_table_name };
+    let destination_table = get_table_name(tables, |table| table.entity_name.eq(destination_entity), destination_entity)?;
+
+    let relation = relations.len();
+
+    relations.push(RelationQuery {
+        select: format!(
+            "SELECT \"d\".*, {} FROM {} AS \"d\"",
+            get_key_selection("d", destination_columns),
+            quote_name(&destination_table)
+        ),
+        key_columns: destination_columns.iter().map(|column| quote_column("d", column)).collect(),
+    });
+
+    let (field_type, list, optional) = if fk.is_list(reverse) {
+        (TypeRef::named_nn_list_nn(destination_entity), true, true)
+    } else if reverse || fk.is_optional(reverse) {
+        (TypeRef::named(destination_entity), false, true)
+    } else {
+        (TypeRef::named_nn(destination_entity), false, false)
+    };
+
+    let destination_entity = destination_entity.clone();
+    let destination_columns = destination_columns.clone();
+
+    Ok(Field::new(fk.get_relation_name(reverse).to_lower_camel_case(), field_type, move |ctx| {
+        let source_columns = source_columns.clone();
+        let destination_entity = destination_entity.clone();
+        let destination_columns = destination_columns.clone();
+
+        FieldFuture::new(async move {
+            let row = ctx.parent_value.try_downcast_ref::<DynamicRow>()?;
+
+            // like the data loader, a key with a missing part does not resolve
+            let key = match get_key(row, &source_columns) {
+                Some(key) => key,
+                None if list => return Ok(Some(FieldValue::list(Vec::<FieldValue>::new()))),
+                None => return Ok(None),
+            };
+
+            let rows = load_relation(&ctx, relation, key.clone()).await?;
+
+            if list {
+                return Ok(Some(FieldValue::list(rows.into_iter().map(FieldValue::owned_any))));
+            }
+
+            match rows.into_iter().next() {
+                Some(row) => Ok(Some(FieldValue::owned_any(row))),
+                None if optional => Ok(None),
+                None => Err(error(
+                    format!("{} with {} does not exist", destination_entity, describe_key(&destination_columns, &key)),
+                    NOT_FOUND,
+                )),
+            }
+        })
+    }))
+}
+
+fn generate_many_to_many_field(relation: &ManyToManyMeta, tables: &[TableMeta], relations: &mut Vec<RelationQuery>) -> Result<Field> {
+    let ManyToManyMeta { junction_table_module, source_foreign_key, destination_foreign_key, relation_name, .. } = relation;
+
+    let junction_table = get_table_name(tables, |table| table.entity_module.eq(junction_table_module), junction_table_module)?;
+    let destination_entity = &destination_foreign_key.destination_table_name;
+    let destination_table = get_table_name(tables, |table| table.entity_name.eq(destination_entity), destination_entity)?;
+
+    let join = destination_foreign_key
+        .source_columns
+        .iter()
+        .zip(destination_foreign_key.destination_columns.iter())
+        .map(|(junction_column, destination_column)| {
+            format!("{} = {}", quote_column("j", junction_column), quote_column("d", destination_column))
+        })
+        .collect::<Vec<String>>()
+        .join(" AND ");
+
+    let relation = relations.len();
+
+    relations.push(RelationQuery {
+        select: format!(
+            "SELECT \"d\".*, {} FROM {} AS \"d\" JOIN {} AS \"j\" ON {}",
+            get_key_selection("j", &source_foreign_key.source_columns),
+            quote_name(&destination_table),
+            quote_name(&junction_table),
+            join
+        ),
+        key_columns: source_foreign_key.source_columns.iter().map(|column| quote_column("j", column)).collect(),
+    });
+
+    let source_columns = source_foreign_key.destination_columns.clone();
+
+    Ok(Field::new(relation_name.to_lower_camel_case(), TypeRef::named_nn_list_nn(destination_entity), move |ctx| {
+        let source_columns = source_columns.clone();
+
+        FieldFuture::new(async move {
+            let row = ctx.parent_value.try_downcast_ref::<DynamicRow>()?;
+
+            let rows = match get_key(row, &source_columns) {
+                Some(key) => load_relation(&ctx, relation, key).await?,
+                None => vec![],
+            };
+
+            Ok(Some(FieldValue::list(rows.into_iter().map(FieldValue::owned_any))))
+        })
+    }))
+}
+
+async fn load_relation(ctx: &ResolverContext<'_>, relation: usize, values: Vec<SqlValue>) -> async_graphql::Result<Vec<DynamicRow>> {
+    let loader = ctx.data::<DataLoader<RelationLoader>>()?;
+
+    Ok(loader.load_one(RelationKey { relation, values }).await?.unwrap_or_default())
+}
+
+// one query per parent row, like the hierarchy resolvers of the generated project
+fn generate_hierarchy_field(table: &TableMeta, fk: &ForeignKeyMeta, relation_name: &str, ancestors: bool) -> Field {
+    let (sql, self_columns) = get_hierarchy_sql(table, fk, ancestors);
+    let self_columns = self_columns.clone();
+
+    Field::new(relation_name.to_lower_camel_case(), TypeRef::named_nn_list_nn(&table.entity_name), move |ctx| {
+        let self_columns = self_columns.clone();
+        let sql = sql.clone();
+
+        FieldFuture::new(async move {
+            let connection = ctx.data::<SqlitePool>()?;
+            let row = ctx.parent_value.try_downcast_ref::<DynamicRow>()?;
+
+            let depth = match get_argument(&ctx, "depth") {
+                Some(depth) => SqlValue::Integer(
+                    depth
+                        .clone()
+                        .into_json()
+                        .ok()
+                        .and_then(|depth| depth.as_u64())
+                        .ok_or_else(|| error("depth must be a positive integer", INVALID_FILTER))? as i64,
+                ),
+                None => SqlValue::Null,
+            };
+
+            if let SqlValue::Integer(0) = depth {
+                return Ok(Some(FieldValue::list(Vec::<FieldValue>::new())));
+            }
+
+            let mut values: Vec<SqlValue> = self_columns
+                .iter()
+                .map(|column| row.0.get(column).cloned().unwrap_or(SqlValue::Null))
+                .collect();
+
+            values.push(depth);
+
+            let rows = fetch_rows(connection, &sql, values).await?;
+
+            Ok(Some(FieldValue::list(rows.into_iter().map(FieldValue::owned_any))))
+        })
+    })
+    .argument(InputValue::new("depth", TypeRef::named(TypeRef::INT)))
+}
+
+// the same conditions and errors as `filter_recursive` of the generated root
+fn generate_condition(columns: &[FilterColumn], filter: &Value, values: &mut Vec<SqlValue>) -> async_graphql::Result<Option<String>> {
+    let filter = match filter {
+        Value::Object(filter) => filter,
+        _ => return Ok(None),
+    };
+
+    let mut conditions: Vec<String> = vec![];
+
+    for (name, separator) in [("or", " OR "), ("and", " AND ")] {
+        if let Some(Value::List(filters)) = filter.get(name) {
+            if filters.is_empty() {
+                return Err(error(format!("`{}` must not be empty", name), INVALID_FILTER));
+            }
+
+            let mut nested: Vec<String> = vec![];
+
+            for nested_filter in filters.iter() {
+                nested.extend(generate_condition(columns, nested_filter, values)?);
+            }
+
+            if !nested.is_empty() {
+                conditions.push(format!("({})", nested.join(separator)));
+            }
+        }
+    }
+
+    for column in columns.iter() {
+        if let Some(Value::Object(column_filter)) = filter.get(column.field_name.as_str()) {
+            let column_name = quote_name(&column.database_name);
+
+            for (name, operator) in [("eq", "="), ("ne", "<>"), ("gt", ">"), ("gte", ">="), ("lt", "<"), ("lte", "<=")] {
+                match column_filter.get(name) {
+                    None | Some(Value::Null) => {}
+                    Some(value) => {
+                        conditions.push(format!("{} {} ?", column_name, operator));
+                        values.push(from_graphql_value(value, column.scalar));
+                    }
+                }
+            }
+
+            for (name, operator) in [("isIn", "IN"), ("isNotIn", "NOT IN")] {
+                if let Some(Value::List(list)) = column_filter.get(name) {
+                    if list.is_empty() {
+                        return Err(error(format!("`{}` of `{}` must not be empty", name, column.field_name), INVALID_FILTER));
+                    }
+
+                    let placeholders: Vec<&str> = list.iter().map(|_| "?").collect();
+
+                    conditions.push(format!("{} {} ({})", column_name, operator, placeholders.join(", ")));
+                    values.extend(list.iter().map(|value| from_graphql_value(value, column.scalar)));
+                }
+            }
+
+            if let Some(Value::Boolean(is_null)) = column_filter.get("isNull") {
+                let operator = if *is_null { "IS NULL" } else { "IS NOT NULL" };
+
+                conditions.push(format!("{} {}", column_name, operator));
+            }
+        }
+    }
+
+    if conditions.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(format!("({})", conditions.join(" AND "))))
+    }
+}
+
+fn get_argument<'a>(ctx: &'a ResolverContext, name: &str) -> Option<&'a Value> {
+    ctx.args
+        .get(name)
+        .map(|argument| argument.as_value())
+        .filter(|value| !matches!(value, Value::Null))
+}
+
+fn get_pagination_value(pagination: &Value, name: &str) -> async_graphql::Result<u64> {
+    match pagination {
+        Value::Object(pagination) => match pagination.get(name) {
+            Some(Value::Number(number)) => number.as_u64(),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(|| error(format!("pagination {} must be a positive integer", name), INVALID_FILTER))
+}
+
+fn get_key(row: &DynamicRow, columns: &[String]) -> Option<Vec<SqlValue>> {
+    columns
+        .iter()
+        .map(|column| match row.0.get(column) {
+            None | Some(SqlValue::Null) => None,
+            Some(value) => Some(value.clone()),
+        })
+        .collect()
+}
+
+// e.g. "ArtistId = 5", for error messages
+fn describe_key(columns: &[String], key: &[SqlValue]) -> String {
+    columns
+        .iter()
+        .zip(key.iter())
+        .map(|(column, value)| match value {
+            SqlValue::Null => format!("{} = null", column),
+            SqlValue::Integer(value) => format!("{} = {}", column, value),
+            SqlValue::Real(value) => format!("{} = {}", column, value),
+            SqlValue::Text(value) => format!("{} = {:?}", column, value),
+            SqlValue::Blob(value) => format!("{} = {:?}", column, String::from_utf8_lossy(value)),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// tables left out by the configuration or a plugin can still be named by a relation
+fn get_table_name(tables: &[TableMeta], predicate: impl Fn(&TableMeta) -> bool, name: &str) -> Result<String> {
+    tables
+        .iter()
+        .find(|table| predicate(table))
+        .map(|table| table.table_name.clone())
+        .ok_or_else(|| GeneratorError::MissingTable(name.into()))
+}
+
+// e.g. `"d"."ArtistId" AS "__key_0"`
+fn get_key_selection(prefix: &str, columns: &[String]) -> String {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| format!("{} AS \"__key_{}\"", quote_column(prefix, column), index))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn quote_name(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_column(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        quote_name(name)
+    } else {
+        format!("{}.{}", quote_name(prefix), quote_name(name))
+    }
+}
+
+async fn fetch_rows(connection: &SqlitePool, sql: &str, values: Vec<SqlValue>) -> async_graphql::Result<Vec<DynamicRow>> {
+    let rows: Vec<SqliteRow> = sqlx::query_with(sql, get_arguments(values))
+        .fetch_all(connection)
+        .await
+        .map_err(|error| database_error(&error))?;
+
+    rows.iter()
+        .map(|row| decode_row(row).map_err(|error| database_error(&error)))
+        .collect()
+}
+
+fn get_arguments<'q>(values: Vec<SqlValue>) -> SqliteArguments<'q> {
+    let mut arguments = SqliteArguments::default();
+
+    for value in values.into_iter() {
+        match value {
+            SqlValue::Null => arguments.add(Option::<i64>::None),
+            SqlValue::Integer(value) => arguments.add(value),
+            SqlValue::Real(value) => arguments.add(value),
+            SqlValue::Text(value) => arguments.add(value),
+            SqlValue::Blob(value) => arguments.add(value),
+        }
+    }
+
+    arguments
+}
+
+// SQLite values carry their own storage class, the declared column type is only a hint
+fn decode_row(row: &SqliteRow) -> std::result::Result<DynamicRow, sqlx::Error> {
+    let mut values: BTreeMap<String, SqlValue> = BTreeMap::new();
+
+    for column in row.columns().iter() {
+        let index = column.ordinal();
+        let raw = row.try_get_raw(index)?;
+
+        let value = if raw.is_null() {
+            SqlValue::Null
+        } else {
+            match raw.type_info().name() {
+                "INTEGER" => SqlValue::Integer(row.try_get_unchecked(index)?),
+                "REAL" => SqlValue::Real(row.try_get_unchecked(index)?),
+                "BLOB" => SqlValue::Blob(row.try_get_unchecked(index)?),
+                _ => SqlValue::Text(row.try_get_unchecked(index)?),
+            }
+        };
+
+        values.insert(column.name().to_string(), value);
+    }
+
+    Ok(DynamicRow(values))
+}
+
+// the representation async-graphql gives the types of the generated entities
+fn to_graphql_value(value: &SqlValue, scalar: &str) -> Value {
+    match (value, scalar) {
+        (SqlValue::Null, _) => Value::Null,
+        (SqlValue::Integer(value), "Boolean") => Value::from(*value != 0),
+        (SqlValue::Integer(value), "Int") => Value::from(*value),
+        (SqlValue::Integer(value), "Float") => Value::from(*value as f64),
+        (SqlValue::Real(value), "Int") => Value::from(*value as i64),
+        (SqlValue::Real(value), "Float") => Value::from(*value),
+        (SqlValue::Text(value), "NaiveDateTime") => Value::from(value.replacen(' ', "T", 1)),
+        (SqlValue::Text(value), _) => Value::from(value.clone()),
+        (SqlValue::Integer(value), _) => Value::from(value.to_string()),
+        (SqlValue::Real(value), _) => Value::from(value.to_string()),
+        (SqlValue::Blob(value), _) => Value::from(String::from_utf8_lossy(value).to_string()),
+    }
+}
+
+fn from_graphql_value(value: &Value, scalar: &str) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Boolean(value) => SqlValue::Integer(*value as i64),
+        Value::Number(number) => match number.as_i64() {
+            Some(number) => SqlValue::Integer(number),
+            None => SqlValue::Real(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) if scalar.eq("NaiveDateTime") => SqlValue::Text(value.replacen('T', " ", 1)),
+        Value::String(value) => SqlValue::Text(value.clone()),
+        value => SqlValue::Text(value.to_string()),
+    }
+}
+
+fn database_error(db_error: &sqlx::Error) -> async_graphql::Error {
+    match db_error {
+        sqlx::Error::RowNotFound => error(db_error.to_string(), NOT_FOUND),
+        _ => error(db_error.to_string(), DATABASE_ERROR),
+    }
+}
+
+fn error(message: impl Into<String>, code: &'static str) -> async_graphql::Error {
+    async_graphql::Error::new(message).extend_with(|_, extensions| extensions.set("code", code))
+}
@@ -1,16 +1,64 @@
+use crate::error::{GeneratorError, Result};
 use sea_orm_codegen::{EntityTransformer, EntityWriter, OutputFile, WithSerde};
 use sea_schema::sea_query::table::TableCreateStatement;
-use std::{fs, io::Result, path::Path};
+use std::{collections::BTreeMap, fs, path::Path};
 
-pub fn generate_entities(dir: &Path, table_create_stmts: Vec<TableCreateStatement>) -> Result<()> {
-    let entity_writer: EntityWriter = EntityTransformer::transform(table_create_stmts).unwrap();
+pub fn generate_entities_files(table_create_stmts: Vec<TableCreateStatement>) -> Result<BTreeMap<String, String>> {
+    let entity_writer: EntityWriter = EntityTransformer::transform(table_create_stmts)
+        .map_err(|error| GeneratorError::EntityGeneration(error.to_string()))?;
 
     let writer_output = entity_writer.generate(true, WithSerde::None);
 
-    for OutputFile { name, content } in writer_output.files.iter() {
+    let files = writer_output
+        .files
+        .into_iter()
+        .map(|OutputFile { name, content }| {
+            // sea-orm-codegen lists modules in hash map order
+            let content = if name.eq("mod.rs") || name.eq("prelude.rs") {
+                sort_statements(&content)
+            } else {
+                content
+            };
+
+            (name, content)
+        })
+        .collect();
+
+    Ok(files)
+}
+
+pub fn generate_entities(dir: &Path, table_create_stmts: Vec<TableCreateStatement>) -> Result<()> {
+    for (name, content) in generate_entities_files(table_create_stmts)?.iter() {
         let file_path = dir.join(name);
         fs::write(file_path, content.as_bytes())?;
     }
 
     Ok(())
 }
+
+// sorts each consecutive run of `pub` statements, leaving comments and blank lines in place
+fn sort_statements(content: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut statements: Vec<&str> = Vec::new();
+
+    for line in content.lines() {
+        if line.starts_with("pub ") {
+            statements.push(line);
+        } else {
+            statements.sort_unstable();
+            lines.append(&mut statements);
+            lines.push(line);
+        }
+    }
+
+    statements.sort_unstable();
+    lines.append(&mut statements);
+
+    let mut sorted = lines.join("\n");
+
+    if content.ends_with('\n') {
+        sorted.push('\n');
+    }
+
+    sorted
+}
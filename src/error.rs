@@ -0,0 +1,88 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum GeneratorError {
+    Connection(sqlx::Error),
+    Discovery(String),
+    UnsupportedColumnType {
+        table: String,
+        column: String,
+        column_type: String,
+    },
+    MissingTable(String),
+    MissingColumn {
+        table: String,
+        column: String,
+    },
+    EntityGeneration(String),
+    InvalidConfig(String),
+    InvalidSnapshot(String),
+    InvalidSdl(String),
+    // the committed `schema.graphql` `schema-check` compares with
+    MissingSdl(String),
+    DynamicSchema(String),
+    Plugin(String),
+    // generated code that does not parse as a Rust file
+    Formatting {
+        file: String,
+        message: String,
+    },
+    // generated JSON or TOML, e.g. a snapshot or `Cargo.toml`
+    Serialization {
+        file: String,
+        message: String,
+    },
+    // a command the generator runs on the generated project (`cargo`) failed
+    Command(String),
+    // command line arguments that do not match the command
+    Usage(String),
+    Io(std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, GeneratorError>;
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::Connection(error) => write!(f, "cannot connect to the database: {}", error),
+            GeneratorError::Discovery(error) => write!(f, "cannot discover the database schema: {}", error),
+            GeneratorError::UnsupportedColumnType { table, column, column_type } => {
+                write!(f, "unsupported type `{}` of column `{}.{}`", column_type, table, column)
+            }
+            GeneratorError::MissingTable(table) => write!(f, "referenced table `{}` not found", table),
+            GeneratorError::MissingColumn { table, column } => {
+                write!(f, "referenced column `{}.{}` not found", table, column)
+            }
+            GeneratorError::EntityGeneration(message) => write!(f, "cannot generate entities: {}", message),
+            GeneratorError::InvalidConfig(message) => write!(f, "invalid configuration: {}", message),
+            GeneratorError::InvalidSnapshot(message) => write!(f, "invalid schema snapshot: {}", message),
+            GeneratorError::InvalidSdl(message) => write!(f, "invalid GraphQL schema: {}", message),
+            GeneratorError::MissingSdl(path) => {
+                write!(f, "`{}` is missing, run `cargo run -- --print-schema` in the project first", path)
+            }
+            GeneratorError::DynamicSchema(message) => write!(f, "cannot build the dynamic schema: {}", message),
+            GeneratorError::Plugin(message) => write!(f, "plugin failed: {}", message),
+            GeneratorError::Formatting { file, message } => write!(f, "cannot format `{}`: {}", file, message),
+            GeneratorError::Serialization { file, message } => write!(f, "cannot serialize `{}`: {}", file, message),
+            GeneratorError::Command(message) => write!(f, "command failed: {}", message),
+            GeneratorError::Usage(usage) => write!(f, "usage: {}", usage),
+            GeneratorError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GeneratorError::Connection(error) => Some(error),
+            GeneratorError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for GeneratorError {
+    fn from(error: std::io::Error) -> Self {
+        GeneratorError::Io(error)
+    }
+}
@@ -0,0 +1,271 @@
+use crate::error::{GeneratorError, Result};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+
+// pretty printed in-process, the output reads the same whether `rustfmt` is installed or not
+pub fn format_tokens(name: &str, tokens: &TokenStream) -> Result<String> {
+    let file: syn::File = syn::parse2(tokens.clone()).map_err(|error| formatting_error(name, error))?;
+
+    Ok(prettyplease::unparse(&file))
+}
+
+// for code only available as text, e.g. the entities of sea-orm-codegen
+pub fn format_source(name: &str, source: &str) -> Result<String> {
+    let file: syn::File = syn::parse_file(source).map_err(|error| formatting_error(name, error))?;
+
+    Ok(prettyplease::unparse(&file))
+}
+
+// the tokens of the syntax tree with imports and module declarations sorted the way `cargo fmt` sorts them,
+// spans and trailing commas are left out so a generated file and the same file after `cargo fmt` compare equal
+pub fn syntax_tokens(name: &str, source: &str) -> Result<Vec<String>> {
+    let mut file: syn::File = syn::parse_file(source).map_err(|error| formatting_error(name, error))?;
+
+    sort_items(&mut file.items);
+    BlockBodies.visit_file_mut(&mut file);
+
+    let mut tokens: Vec<String> = Vec::new();
+    push_tokens(file.into_token_stream(), &mut tokens);
+
+    Ok(tokens)
+}
+
+fn push_tokens(stream: TokenStream, tokens: &mut Vec<String>) {
+    let mut trees = stream.into_iter().peekable();
+
+    while let Some(tree) = trees.next() {
+        match tree {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+
+                tokens.push(open.into());
+                push_tokens(group.stream(), tokens);
+                tokens.push(close.into());
+            }
+            // a comma closing a group or a list of generic arguments is optional
+            TokenTree::Punct(punct) if punct.as_char().eq(&',') && closes_list(trees.peek()) => {}
+            TokenTree::Punct(punct) => tokens.push(punct.as_char().into()),
+            TokenTree::Ident(ident) => tokens.push(ident.to_string()),
+            TokenTree::Literal(literal) => tokens.push(literal.to_string()),
+        }
+    }
+}
+
+fn closes_list(next: Option<&TokenTree>) -> bool {
+    match next {
+        None => true,
+        Some(TokenTree::Punct(punct)) => punct.as_char().eq(&'>'),
+        _ => false,
+    }
+}
+
+// `cargo fmt` adds and removes the braces around closure bodies and match arms,
+// a block holding a single expression is compared as that expression
+struct BlockBodies;
+
+impl VisitMut for BlockBodies {
+    fn visit_expr_closure_mut(&mut self, closure: &mut syn::ExprClosure) {
+        visit_mut::visit_expr_closure_mut(self, closure);
+        unwrap_block(&mut closure.body);
+    }
+
+    fn visit_arm_mut(&mut self, arm: &mut syn::Arm) {
+        visit_mut::visit_arm_mut(self, arm);
+        unwrap_block(&mut arm.body);
+        arm.comma = None;
+    }
+}
+
+fn unwrap_block(expr: &mut syn::Expr) {
+    let inner: Option<syn::Expr> = match expr {
+        syn::Expr::Block(syn::ExprBlock { attrs, label: None, block }) if attrs.is_empty() => match block.stmts.as_slice() {
+            [syn::Stmt::Expr(inner, None)] => Some(inner.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    if let Some(inner) = inner {
+        *expr = inner;
+    }
+}
+
+fn sort_items(items: &mut [syn::Item]) {
+    for item in items.iter_mut() {
+        match item {
+            syn::Item::Use(item_use) => sort_use_tree(&mut item_use.tree),
+            syn::Item::Mod(syn::ItemMod { content: Some((_, items)), .. }) => sort_items(items),
+            _ => {}
+        }
+    }
+
+    // only runs of adjacent `use` items and of adjacent `mod name;` declarations are reordered
+    let mut start = 0;
+
+    while start < items.len() {
+        let kind = sortable_kind(&items[start]);
+        let end = start + items[start..].iter().take_while(|item| kind.is_some() && sortable_kind(item).eq(&kind)).count().max(1);
+
+        items[start..end].sort_by_cached_key(|item| item.to_token_stream().to_string());
+
+        start = end;
+    }
+}
+
+fn sortable_kind(item: &syn::Item) -> Option<&'static str> {
+    match item {
+        syn::Item::Use(_) => Some("use"),
+        syn::Item::Mod(syn::ItemMod { content: None, .. }) => Some("mod"),
+        _ => None,
+    }
+}
+
+fn sort_use_tree(tree: &mut syn::UseTree) {
+    match tree {
+        syn::UseTree::Path(path) => sort_use_tree(&mut path.tree),
+        syn::UseTree::Group(group) => {
+            let mut trees: Vec<syn::UseTree> = group.items.iter().cloned().collect();
+
+            trees.iter_mut().for_each(sort_use_tree);
+            trees.sort_by_cached_key(|tree| tree.to_token_stream().to_string());
+
+            // `cargo fmt` writes `a::{b}` as `a::b`
+            if trees.len() == 1 {
+                *tree = trees.remove(0);
+            } else {
+                group.items = trees.into_iter().collect();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn formatting_error(name: &str, error: syn::Error) -> GeneratorError {
+    GeneratorError::Formatting {
+        file: name.into(),
+        message: error.to_string(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{format_source, syntax_tokens};
+
+    // as generated, the braces and commas are the ones `prettyplease` prints
+    const GENERATED: &str = r#"
+use sea_orm::prelude::*;
+use crate::orm::{albums};
+use async_graphql::Context;
+
+pub type QuerySchema = async_graphql::Schema<Query, async_graphql::EmptyMutation, async_graphql::EmptySubscription,>;
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Artists => { Entity::belongs_to(super::artists::Entity).from(Column::ArtistId).to(super::artists::Column::ArtistId).into() }
+        }
+    }
+}
+
+pub async fn artist(db: &DatabaseConnection, id: i32) -> async_graphql::Result<albums::Model> {
+    let data = albums::Entity::find_by_id(id).one(db).await.map_err(|error| errors::database_error(&error))?;
+    data.ok_or_else(|| errors::not_found(format!("Albums with AlbumId = {:?} does not exist, looked up for the artist of the album", id)))
+}
+
+pub const COLUMNS: [&str; 4] = ["AlbumId", "Title", "ArtistId", "A column with a long name to wrap the array"];
+
+pub fn columns() -> Vec<String> {
+    vec![String::from("AlbumId"), String::from("Title"), String::from("ArtistId"), String::from("Other")]
+}
+"#;
+
+    // `GENERATED` after `cargo fmt`
+    const FORMATTED: &str = r#"
+use crate::orm::albums;
+use async_graphql::Context;
+use sea_orm::prelude::*;
+
+pub type QuerySchema =
+    async_graphql::Schema<Query, async_graphql::EmptyMutation, async_graphql::EmptySubscription>;
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Artists => Entity::belongs_to(super::artists::Entity)
+                .from(Column::ArtistId)
+                .to(super::artists::Column::ArtistId)
+                .into(),
+        }
+    }
+}
+
+pub async fn artist(db: &DatabaseConnection, id: i32) -> async_graphql::Result<albums::Model> {
+    let data = albums::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|error| errors::database_error(&error))?;
+    data.ok_or_else(|| {
+        errors::not_found(format!(
+            "Albums with AlbumId = {:?} does not exist, looked up for the artist of the album",
+            id
+        ))
+    })
+}
+
+pub const COLUMNS: [&str; 4] = [
+    "AlbumId",
+    "Title",
+    "ArtistId",
+    "A column with a long name to wrap the array",
+];
+
+pub fn columns() -> Vec<String> {
+    vec![
+        String::from("AlbumId"),
+        String::from("Title"),
+        String::from("ArtistId"),
+        String::from("Other"),
+    ]
+}
+"#;
+
+    #[test]
+    fn cargo_fmt_round_trip() {
+        let generated = format_source("albums.rs", GENERATED).unwrap();
+
+        assert_ne!(generated, FORMATTED);
+        assert_eq!(syntax_tokens("albums.rs", &generated).unwrap(), syntax_tokens("albums.rs", FORMATTED).unwrap());
+    }
+
+    #[test]
+    fn changed_code_differs() {
+        let changed = FORMATTED.replace("\"Title\"", "\"Name\"");
+
+        assert_ne!(syntax_tokens("albums.rs", &changed).unwrap(), syntax_tokens("albums.rs", FORMATTED).unwrap());
+    }
+
+    #[test]
+    fn blocks_with_statements_are_kept() {
+        let block = "fn f() { g(|| { h(); i() }) }";
+        let expression = "fn f() { g(|| i()) }";
+
+        assert_ne!(syntax_tokens("f.rs", block).unwrap(), syntax_tokens("f.rs", expression).unwrap());
+    }
+
+    #[test]
+    fn only_adjacent_imports_are_sorted() {
+        let sorted = "use a::b;\nuse c::d;\nfn f() {}\nmod x;\nmod y;\n";
+        let unsorted = "use c::d;\nuse a::{b};\nfn f() {}\nmod y;\nmod x;\n";
+        let moved = "use a::b;\nfn f() {}\nuse c::d;\nmod x;\nmod y;\n";
+
+        assert_eq!(syntax_tokens("lib.rs", sorted).unwrap(), syntax_tokens("lib.rs", unsorted).unwrap());
+        assert_ne!(syntax_tokens("lib.rs", sorted).unwrap(), syntax_tokens("lib.rs", moved).unwrap());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{
+    config::GeneratorConfig,
+    database_schema::get_database_schema,
+    error::{GeneratorError, Result},
+    graphql::generate_extensions,
+    plugin::{apply_plugins, GeneratorPlugin},
+    project_generator::{generate_project, write_project},
+    types::{ForeignKeyMeta, TableMeta},
+};
+use sea_schema::sea_query::table::TableCreateStatement;
+use sqlx::{Pool, Sqlite, SqlitePool};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub enum SchemaSource {
+    Sqlite(String), // connection url, e.g. `sqlite://chinook.db`
+    Connection(Pool<Sqlite>),
+}
+
+// everything generated for one project, keyed by paths relative to the project directory
+pub struct GeneratedProject {
+    pub files: BTreeMap<String, String>,
+    // user owned files, only written when missing
+    pub extension_files: BTreeMap<String, String>,
+    pub tables_meta: Vec<TableMeta>,
+    pub inferred_foreign_keys: Vec<ForeignKeyMeta>,
+}
+
+pub trait OutputSink {
+    fn write(&self, project: &GeneratedProject) -> Result<()>;
+}
+
+pub struct DirectorySink {
+    project_dir: PathBuf,
+}
+
+impl DirectorySink {
+    pub fn new(project_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            project_dir: project_dir.into(),
+        }
+    }
+}
+
+impl OutputSink for DirectorySink {
+    fn write(&self, project: &GeneratedProject) -> Result<()> {
+        write_project(&self.project_dir, &project.files)?;
+
+        let missing_extension_files: BTreeMap<String, String> = project
+            .extension_files
+            .iter()
+            .filter(|(name, _)| !self.project_dir.join(name).exists())
+            .map(|(name, content)| (name.clone(), content.clone()))
+            .collect();
+
+        write_project(&self.project_dir, &missing_extension_files)?;
+
+        // an existing `mod.rs` is kept, only the modules of new tables are declared in it
+        let modules: Vec<&str> = missing_extension_files
+            .keys()
+            .filter_map(|name| name.strip_prefix("src/extensions/")?.strip_suffix(".rs"))
+            .filter(|module| module.ne(&"mod"))
+            .collect();
+
+        if !missing_extension_files.contains_key(EXTENSIONS_MOD) {
+            declare_modules(&self.project_dir.join(EXTENSIONS_MOD), &modules)?;
+        }
+
+        Ok(())
+    }
+}
+
+const EXTENSIONS_MOD: &str = "src/extensions/mod.rs";
+
+// appends `pub mod <module>;` for every module the file does not declare yet
+fn declare_modules(mod_path: &Path, modules: &[&str]) -> Result<()> {
+    let mut content = fs::read_to_string(mod_path)?;
+
+    let file = syn::parse_file(&content).map_err(|error| GeneratorError::Formatting {
+        file: EXTENSIONS_MOD.into(),
+        message: error.to_string(),
+    })?;
+
+    let declared: Vec<String> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Mod(item_mod) => Some(item_mod.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let missing: Vec<&&str> = modules
+        .iter()
+        .filter(|module| !declared.iter().any(|declared| declared.eq(**module)))
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+
+    for module in missing.iter() {
+        content.push_str(&format!("pub mod {};\n", module));
+    }
+
+    Ok(fs::write(mod_path, content)?)
+}
+
+// `Generator::new(source).project_name("api").sink(DirectorySink::new("api")).generate()`
+pub struct Generator {
+    source: SchemaSource,
+    project_name: String,
+    config: GeneratorConfig,
+    plugins: Vec<Box<dyn GeneratorPlugin>>,
+    sink: Option<Box<dyn OutputSink>>,
+}
+
+impl Generator {
+    pub fn new(source: SchemaSource) -> Self {
+        Self {
+            source,
+            project_name: "generated".into(),
+            config: GeneratorConfig::default(),
+            plugins: Vec::new(),
+            sink: None,
+        }
+    }
+
+    pub fn project_name(mut self, project_name: impl Into<String>) -> Self {
+        self.project_name = project_name.into();
+        self
+    }
+
+    pub fn config(mut self, config: GeneratorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config_file(self, path: &Path) -> Result<Self> {
+        let config = GeneratorConfig::load(path)?;
+
+        Ok(self.config(config))
+    }
+
+    // plugins run in the order they are added
+    pub fn plugin(mut self, plugin: impl GeneratorPlugin + 'static) -> Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    // without a sink the project is only returned
+    pub fn sink(mut self, sink: impl OutputSink + 'static) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    pub async fn generate(&self) -> Result<GeneratedProject> {
+        let (tables_meta, table_create_stmts, inferred_foreign_keys) = self.load_schema().await?;
+
+        self.generate_from_schema(tables_meta, table_create_stmts, inferred_foreign_keys)
+    }
+
+    // the discovered schema with the configuration applied, see `get_database_schema`
+    pub async fn load_schema(&self) -> Result<(Vec<TableMeta>, Vec<TableCreateStatement>, Vec<ForeignKeyMeta>)> {
+        let connection = match &self.source {
+            SchemaSource::Sqlite(url) => SqlitePool::connect(url)
+                .await
+                .map_err(GeneratorError::Connection)?,
+            SchemaSource::Connection(connection) => connection.clone(),
+        };
+
+        get_database_schema(connection, &self.config).await
+    }
+
+    pub fn generate_from_schema(
+        &self,
+        tables_meta: Vec<TableMeta>,
+        table_create_stmts: Vec<TableCreateStatement>,
+        inferred_foreign_keys: Vec<ForeignKeyMeta>,
+    ) -> Result<GeneratedProject> {
+        let tables_meta = apply_plugins(&self.plugins, tables_meta);
+
+        let files = generate_project(&self.project_name, &tables_meta, table_create_stmts, &self.config, &self.plugins)?;
+
+        let extension_files: BTreeMap<String, String> = generate_extensions(&tables_meta)?
+            .into_iter()
+            .map(|(name, content)| (format!("src/extensions/{}", name), content))
+            .collect();
+
+        let project = GeneratedProject {
+            files,
+            extension_files,
+            tables_meta,
+            inferred_foreign_keys,
+        };
+
+        if let Some(sink) = &self.sink {
+            sink.write(&project)?;
+        }
+
+        Ok(project)
+    }
+}
@@ -1,26 +1,47 @@
-use crate::types::{ColumnMeta, ForeignKeyMeta, TableMeta};
-use heck::ToSnakeCase;
+use crate::{
+    plugin::Plugins,
+    types::{ColumnMeta, ForeignKeyMeta, ManyToManyMeta, TableMeta},
+};
+use heck::{ToSnakeCase, ToUpperCamelCase};
 use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{format_ident, quote};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-pub fn generate_graphql_entities(tables_meta: &Vec<TableMeta>) -> HashMap<String, TokenStream> {
-    let entities: HashMap<String, TokenStream> = tables_meta
+pub fn generate_graphql_entities(tables_meta: &[TableMeta], plugins: &Plugins) -> BTreeMap<String, TokenStream> {
+    let entities: BTreeMap<String, TokenStream> = tables_meta
         .iter()
         .map(|table: &TableMeta| {
             let entity_module: Ident = format_ident!("{}", table.entity_module);
-            let entity_name = format!("{}", table.entity_name);
-            let entity_filter = format!("{}Filter", table.entity_name);
+            let entity_name = table.entity_name.to_string();
+            let entity_filter = table.filter_name.clone();
 
             let filters: Vec<TokenStream> = generate_entity_filters(table);
-            let getters: Vec<TokenStream> = generate_entity_getters(table);
+            let getters: Vec<TokenStream> = generate_entity_getters(table, plugins);
             let relations: Vec<TokenStream> = generate_entity_relations(table);
             let foreign_keys: Vec<TokenStream> = generate_foreign_keys_and_loaders(table);
+            let many_to_many_relations: Vec<TokenStream> = generate_many_to_many_relations(table);
+            let many_to_many_loaders: Vec<TokenStream> = generate_many_to_many_loaders(table);
+            let hierarchy_relations: Vec<TokenStream> = generate_hierarchy_relations(table);
+
+            let filter_derives: Vec<TokenStream> = plugins.iter().flat_map(|plugin| plugin.filter_derives(table)).collect();
+            let plugin_resolvers: Vec<TokenStream> = plugins.iter().flat_map(|plugin| plugin.entity_resolvers(table)).collect();
+            let plugin_items: Vec<TokenStream> = plugins.iter().flat_map(|plugin| plugin.entity_items(table)).collect();
+
+            // `into_group_map` of the loaders returning lists, e.g. junction tables have none
+            let uses_itertools = !table.many_to_many.is_empty()
+                || get_relation_directions(table).into_iter().any(|(fk, reverse)| fk.is_list(reverse));
+            let itertools_import: TokenStream = if uses_itertools {
+                quote! {
+                    use itertools::Itertools;
+                }
+            } else {
+                quote! {}
+            };
 
             let entity_tokens: TokenStream = quote! {
                 use async_graphql::Context;
                 use sea_orm::prelude::*;
-                use itertools::Itertools;
+                #itertools_import
 
                 // TODO generate filter parser function
 
@@ -31,9 +52,17 @@ pub fn generate_graphql_entities(tables_meta: &Vec<TableMeta>) -> HashMap<String
                 impl Model {
                     #(#getters)*
                     #(#relations)*
+                    #(#many_to_many_relations)*
+                    #(#hierarchy_relations)*
+                    #(#plugin_resolvers)*
+
+                    #[graphql(flatten)]
+                    async fn graphql_extension(&self) -> crate::extensions::#entity_module::ModelExtension<'_> {
+                        crate::extensions::#entity_module::ModelExtension(self)
+                    }
                 }
 
-                #[derive(async_graphql::InputObject, Debug)]
+                #[derive(async_graphql::InputObject, Debug #(, #filter_derives)*)]
                 #[graphql(name=#entity_filter)]
                 pub struct Filter {
                     pub or: Option<Vec<Box<Filter>>>,
@@ -42,6 +71,10 @@ pub fn generate_graphql_entities(tables_meta: &Vec<TableMeta>) -> HashMap<String
                 }
 
                 #(#foreign_keys)*
+
+                #(#many_to_many_loaders)*
+
+                #(#plugin_items)*
             };
 
             (table.entity_module.clone(), entity_tokens)
@@ -56,26 +89,30 @@ pub fn generate_entity_filters(table: &TableMeta) -> Vec<TokenStream> {
         .columns
         .iter()
         .map(|column: &ColumnMeta| {
-            let column_name = format_ident!("{}", column.column_name);
+            let field_name = format_ident!("{}", column.field_name);
             let column_filter_type = column.column_filter_type.clone();
 
             quote! {
-                pub #column_name: Option<TypeFilter<#column_filter_type>>
+                pub #field_name: Option<TypeFilter<#column_filter_type>>
             }
         })
         .collect()
 }
 
-pub fn generate_entity_getters(table: &TableMeta) -> Vec<TokenStream> {
+pub fn generate_entity_getters(table: &TableMeta, plugins: &Plugins) -> Vec<TokenStream> {
     table
         .columns
         .iter()
         .map(|column: &ColumnMeta| {
             let column_name = format_ident!("{}", column.column_name);
+            let field_name = format_ident!("{}", column.field_name);
             let column_type = column.column_type.clone();
 
+            let attributes: Vec<TokenStream> = plugins.iter().flat_map(|plugin| plugin.column_attributes(table, column)).collect();
+
             quote! {
-                pub async fn #column_name(&self) -> &#column_type {
+                #(#attributes)*
+                pub async fn #field_name(&self) -> &#column_type {
                     &self.#column_name
                 }
             }
@@ -83,44 +120,45 @@ pub fn generate_entity_getters(table: &TableMeta) -> Vec<TokenStream> {
         .collect()
 }
 
-pub fn generate_entity_relations(table: &TableMeta) -> Vec<TokenStream> {
+// a self referencing key is exposed in both directions on the same table
+pub fn get_relation_directions(table: &TableMeta) -> Vec<(&ForeignKeyMeta, bool)> {
     table
         .foreign_keys
         .iter()
-        .map(|fk: &ForeignKeyMeta| {
-            let reverse = fk.destination_table_name.eq(&table.entity_name);
+        .flat_map(|fk: &ForeignKeyMeta| {
+            let mut directions: Vec<(&ForeignKeyMeta, bool)> = vec![];
 
+            if fk.source_table_name.eq(&table.entity_name) {
+                directions.push((fk, false));
+            }
 
-            let source_entity = if reverse { &fk.destination_table_name } else { &fk.source_table_name };
-            let destination_entity = if reverse { &fk.source_table_name } else { &fk.destination_table_name };
+            if fk.destination_table_name.eq(&table.entity_name) {
+                directions.push((fk, true));
+            }
+
+            directions
+        })
+        .collect()
+}
 
-            let fk_name = format_ident!("{}{}FK", source_entity, destination_entity);
+pub fn generate_entity_relations(table: &TableMeta) -> Vec<TokenStream> {
+    get_relation_directions(table)
+        .into_iter()
+        .map(|(fk, reverse): (&ForeignKeyMeta, bool)| {
+            let fk_name = format_ident!("{}", fk.get_key_name(reverse));
 
             let source_columns = if reverse { &fk.destination_columns } else { &fk.source_columns };
 
-            let source_name = source_columns
-                .clone()
-                .into_iter()
-                .map(|s: String| s.to_snake_case())
-                .map(|s: String| {
-                    if s.ends_with("_id") {
-                        String::from(s.split_at(s.len() - 3).0)
-                    } else {
-                        s
-                    }
-                })
-                .collect::<Vec<String>>()
-                .join("_");
+            let relation_name = format_ident!("{}", fk.get_relation_name(reverse));
 
             let destination_table_module = if reverse { &fk.source_table_module } else { &fk.destination_table_module };
-            let relation_name = format_ident!("{}_{}", source_name, destination_table_module);
             let destination_table_module = format_ident!("{}", destination_table_module);
 
-            let return_type: TokenStream = if reverse {
+            let return_type: TokenStream = if fk.is_list(reverse) {
                 quote! {
                     Vec<crate::orm::#destination_table_module::Model>
                 }
-            } else if fk.is_optional(reverse) {
+            } else if reverse || fk.is_optional(reverse) {
                 quote! {
                     Option<crate::orm::#destination_table_module::Model>
                 }
@@ -130,6 +168,8 @@ pub fn generate_entity_relations(table: &TableMeta) -> Vec<TokenStream> {
                 }
             };
 
+            let destination_table_name = if reverse { &fk.source_table_name } else { &fk.destination_table_name };
+
             // TODO add filter on relation
             // filters: Option<entities::#table_filter>,
 
@@ -140,17 +180,30 @@ pub fn generate_entity_relations(table: &TableMeta) -> Vec<TokenStream> {
                 })
                 .collect();
 
-            let return_value: TokenStream = if reverse {
+            // e.g. "Tracks with TrackId = {:?} does not exist", internal key types stay out of the message
+            let destination_columns = if reverse { &fk.source_columns } else { &fk.destination_columns };
+            let not_found_message = format!(
+                "{} with {} does not exist",
+                destination_table_name,
+                destination_columns
+                    .iter()
+                    .map(|name: &String| format!("{} = {{:?}}", name))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+
+            // a required key without a matching row is a dangling foreign key
+            let return_value: TokenStream = if fk.is_list(reverse) {
                 quote! {
-                    data.unwrap_or(vec![])
+                    Ok(data.unwrap_or(vec![]))
                 }
-            } else if fk.is_optional(reverse) {
+            } else if reverse || fk.is_optional(reverse) {
                 quote! {
-                    data
+                    Ok(data)
                 }
             } else {
                 quote! {
-                    data.unwrap()
+                    data.ok_or_else(|| errors::not_found(format!(#not_found_message, #(self.#key_items),*)))
                 }
             };
 
@@ -158,12 +211,15 @@ pub fn generate_entity_relations(table: &TableMeta) -> Vec<TokenStream> {
                 pub async fn #relation_name<'a>(
                     &self,
                     ctx: &Context<'a>
-                ) -> #return_type {
-                    let data_loader = ctx.data::<async_graphql::dataloader::DataLoader<OrmDataLoader>>().unwrap();
+                ) -> async_graphql::Result<#return_type> {
+                    let data_loader = ctx.data::<async_graphql::dataloader::DataLoader<OrmDataLoader>>()?;
 
-                    let key = #fk_name(#(self.#key_items),*);
+                    let key = #fk_name(#(self.#key_items.clone()),*);
 
-                    let data: Option<_> = data_loader.load_one(key).await.unwrap();
+                    let data: Option<_> = data_loader
+                        .load_one(key)
+                        .await
+                        .map_err(|error| errors::database_error(&error))?;
 
                     #return_value
                 }
@@ -173,31 +229,25 @@ pub fn generate_entity_relations(table: &TableMeta) -> Vec<TokenStream> {
 }
 
 pub fn generate_foreign_keys_and_loaders(table: &TableMeta) -> Vec<TokenStream> {
-    table
-        .foreign_keys
-        .iter()
-        .map(|fk: &ForeignKeyMeta| {
-            let reverse = fk.is_reverse(&table.entity_name);
-
-            let field_indexes: Vec<Literal> = (0..fk.source_column_types.clone().len()).map(|n| Literal::usize_unsuffixed(n)).collect();
+    get_relation_directions(table)
+        .into_iter()
+        .map(|(fk, reverse): (&ForeignKeyMeta, bool)| {
+            let field_indexes: Vec<Literal> = (0..fk.source_column_types.clone().len()).map(Literal::usize_unsuffixed).collect();
 
-            let source_entity = if reverse { &fk.destination_table_name } else { &fk.source_table_name };
             // let source_table_module = if reverse { &fk.destination_table_module } else { &fk.source_table_module };
             // let source_table_module = format_ident!("{}", source_table_module);
             // let source_column_names = if reverse { &fk.destination_columns } else { &fk.source_columns };
             // let source_column_names: Vec<Ident> = source_column_names.iter().map(|name| format_ident!("{}", name.to_snake_case())).collect();
 
-            let destination_entity = if reverse { &fk.source_table_name } else { &fk.destination_table_name };
             let destination_table_module = if reverse { &fk.source_table_module } else { &fk.destination_table_module };
             let destination_table_module = format_ident!("{}", destination_table_module);
             let destination_column_names = if reverse { &fk.source_columns } else { &fk.destination_columns };
-            let destination_columns: Vec<Ident> = destination_column_names.iter().map(|name| format_ident!("{}", name)).collect();
+            let destination_columns: Vec<Ident> = destination_column_names.iter().map(|name| format_ident!("{}", name.to_upper_camel_case())).collect();
             let destination_column_names: Vec<Ident> = destination_column_names.iter().map(|name| format_ident!("{}", name.to_snake_case())).collect();
 
-            let fk_name = format_ident!("{}{}FK", source_entity, destination_entity);
-
+            let fk_name = format_ident!("{}", fk.get_key_name(reverse));
 
-            let return_type: TokenStream = if reverse {
+            let return_type: TokenStream = if fk.is_list(reverse) {
                 quote! {
                     Vec<crate::orm::#destination_table_module::Model>
                 }
@@ -221,21 +271,21 @@ pub fn generate_foreign_keys_and_loaders(table: &TableMeta) -> Vec<TokenStream>
 
                     if source_optional && !destination_optional {
                         quote! {
-                            model.#name.unwrap()
+                            model.#name.clone()?
                         }
                     } else if !source_optional && destination_optional {
                         quote! {
-                            Some(model.#name)
+                            Some(model.#name.clone())
                         }
                     } else {
                         quote! {
-                            model.#name
+                            model.#name.clone()
                         }
                     }
                 })
                 .collect();
 
-            let prepare_step = if reverse {
+            let prepare_step = if fk.is_list(reverse) {
                 quote! {
                     .into_group_map()
                 }
@@ -269,7 +319,7 @@ pub fn generate_foreign_keys_and_loaders(table: &TableMeta) -> Vec<TokenStream>
                                             keys
                                                 .iter()
                                                 .map(|tuple|
-                                                    sea_orm::sea_query::SimpleExpr::Values(vec![#(tuple.#field_indexes.into()),*])
+                                                    sea_orm::sea_query::SimpleExpr::Values(vec![#(tuple.#field_indexes.clone().into()),*])
                                                 )
                                                 .collect()
                                         )
@@ -283,10 +333,11 @@ pub fn generate_foreign_keys_and_loaders(table: &TableMeta) -> Vec<TokenStream>
                                 .all(&self.db)
                                 .await?
                                 .into_iter()
-                                .map(|model| {
+                                // rows with a null key column cannot be referenced
+                                .filter_map(|model| {
                                     let key = #fk_name(#(#destination_fields),*);
 
-                                    (key, model)
+                                    Some((key, model))
                                 })
                                 #prepare_step
                         )
@@ -296,3 +347,440 @@ pub fn generate_foreign_keys_and_loaders(table: &TableMeta) -> Vec<TokenStream>
         })
         .collect()
 }
+
+pub fn generate_many_to_many_relations(table: &TableMeta) -> Vec<TokenStream> {
+    table
+        .many_to_many
+        .iter()
+        .map(|relation: &ManyToManyMeta| {
+            let source_fk = &relation.source_foreign_key;
+            let destination_fk = &relation.destination_foreign_key;
+
+            let key_name = format_ident!("{}", relation.get_key_name());
+
+            let relation_name = format_ident!("{}", relation.relation_name);
+            let destination_table_module = format_ident!("{}", destination_fk.destination_table_module);
+
+            let key_items: Vec<Ident> = source_fk
+                .destination_columns
+                .iter()
+                .map(|name: &String| {
+                    format_ident!("{}", name.to_snake_case())
+                })
+                .collect();
+
+            quote! {
+                pub async fn #relation_name<'a>(
+                    &self,
+                    ctx: &Context<'a>
+                ) -> async_graphql::Result<Vec<crate::orm::#destination_table_module::Model>> {
+                    let data_loader = ctx.data::<async_graphql::dataloader::DataLoader<OrmDataLoader>>()?;
+
+                    let key = #key_name(#(self.#key_items.clone()),*);
+
+                    let data: Option<_> = data_loader
+                        .load_one(key)
+                        .await
+                        .map_err(|error| errors::database_error(&error))?;
+
+                    Ok(data.unwrap_or(vec![]))
+                }
+            }
+        })
+        .collect()
+}
+
+pub fn generate_many_to_many_loaders(table: &TableMeta) -> Vec<TokenStream> {
+    table
+        .many_to_many
+        .iter()
+        .map(|relation: &ManyToManyMeta| {
+            let source_fk = &relation.source_foreign_key;
+            let destination_fk = &relation.destination_foreign_key;
+
+            let key_name = format_ident!("{}", relation.get_key_name());
+
+            let junction_table_module = format_ident!("{}", relation.junction_table_module);
+            let destination_table_module = format_ident!("{}", destination_fk.destination_table_module);
+
+            let key_types = &source_fk.destination_column_types;
+            let key_indexes: Vec<Literal> = (0..key_types.len()).map(Literal::usize_unsuffixed).collect();
+
+            let junction_source_columns: Vec<Ident> = source_fk.source_columns.iter().map(|name| format_ident!("{}", name.to_upper_camel_case())).collect();
+            let junction_source_fields: Vec<TokenStream> = source_fk
+                .source_columns
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    convert_junction_field(
+                        format_ident!("{}", name.to_snake_case()),
+                        &source_fk.source_column_types[index],
+                        &source_fk.destination_column_types[index],
+                    )
+                })
+                .collect();
+            let junction_destination_values: Vec<Ident> = destination_fk.source_columns.iter().map(|name| format_ident!("{}", name.to_snake_case())).collect();
+            let junction_destination_fields: Vec<TokenStream> = destination_fk
+                .source_columns
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    convert_junction_field(
+                        format_ident!("{}", name.to_snake_case()),
+                        &destination_fk.source_column_types[index],
+                        &destination_fk.destination_column_types[index],
+                    )
+                })
+                .collect();
+
+            let destination_types = &destination_fk.destination_column_types;
+            let destination_columns: Vec<Ident> = destination_fk.destination_columns.iter().map(|name| format_ident!("{}", name.to_upper_camel_case())).collect();
+            let destination_fields: Vec<Ident> = destination_fk.destination_columns.iter().map(|name| format_ident!("{}", name.to_snake_case())).collect();
+
+            quote! {
+                #[derive(Clone, Eq, PartialEq, Hash, Debug)]
+                pub struct #key_name(#(#key_types),*);
+
+                #[async_trait::async_trait]
+                impl async_graphql::dataloader::Loader<#key_name> for OrmDataLoader {
+                    type Value = Vec<crate::orm::#destination_table_module::Model>;
+                    type Error = std::sync::Arc<sea_orm::error::DbErr>;
+
+                    async fn load(&self, keys: &[#key_name]) -> Result<std::collections::HashMap<#key_name, Self::Value>, Self::Error> {
+                        let junction_filter = sea_orm::Condition::all()
+                            .add(
+                                sea_orm::sea_query::SimpleExpr::Binary(
+                                    Box::new(
+                                        sea_orm::sea_query::SimpleExpr::Tuple(vec![
+                                            #(sea_orm::sea_query::Expr::col(crate::orm::#junction_table_module::Column::#junction_source_columns.as_column_ref()).into_simple_expr()),*
+                                        ])
+                                    ),
+                                    sea_orm::sea_query::BinOper::In,
+                                    Box::new(
+                                        sea_orm::sea_query::SimpleExpr::Tuple(
+                                            keys
+                                                .iter()
+                                                .map(|tuple|
+                                                    sea_orm::sea_query::SimpleExpr::Values(vec![#(tuple.#key_indexes.clone().into()),*])
+                                                )
+                                                .collect()
+                                        )
+                                    )
+                                )
+                            );
+
+                        let junctions: Vec<crate::orm::#junction_table_module::Model> = crate::orm::#junction_table_module::Entity::find()
+                            .filter(junction_filter)
+                            .all(&self.db)
+                            .await?;
+
+                        if junctions.is_empty() {
+                            return Ok(std::collections::HashMap::new());
+                        }
+
+                        let destination_filter = sea_orm::Condition::all()
+                            .add(
+                                sea_orm::sea_query::SimpleExpr::Binary(
+                                    Box::new(
+                                        sea_orm::sea_query::SimpleExpr::Tuple(vec![
+                                            #(sea_orm::sea_query::Expr::col(crate::orm::#destination_table_module::Column::#destination_columns.as_column_ref()).into_simple_expr()),*
+                                        ])
+                                    ),
+                                    sea_orm::sea_query::BinOper::In,
+                                    Box::new(
+                                        sea_orm::sea_query::SimpleExpr::Tuple(
+                                            junctions
+                                                .iter()
+                                                .map(|junction|
+                                                    sea_orm::sea_query::SimpleExpr::Values(vec![#(junction.#junction_destination_values.clone().into()),*])
+                                                )
+                                                .collect()
+                                        )
+                                    )
+                                )
+                            );
+
+                        let destinations: std::collections::HashMap<(#(#destination_types,)*), crate::orm::#destination_table_module::Model> = crate::orm::#destination_table_module::Entity::find()
+                            .filter(destination_filter)
+                            .all(&self.db)
+                            .await?
+                            .into_iter()
+                            .map(|model| ((#(model.#destination_fields.clone(),)*), model))
+                            .collect();
+
+                        Ok(
+                            junctions
+                                .into_iter()
+                                .filter_map(|junction| {
+                                    let model = destinations.get(&(#(#junction_destination_fields,)*))?.clone();
+
+                                    Some((#key_name(#(#junction_source_fields),*), model))
+                                })
+                                .into_group_map()
+                        )
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+// junction columns may be nullable while the referenced columns are not
+fn convert_junction_field(name: Ident, junction_type: &TokenStream, referenced_type: &TokenStream) -> TokenStream {
+    let junction_optional = junction_type.to_string().starts_with("Option");
+    let referenced_optional = referenced_type.to_string().starts_with("Option");
+
+    if junction_optional && !referenced_optional {
+        quote! {
+            junction.#name.clone()?
+        }
+    } else if !junction_optional && referenced_optional {
+        quote! {
+            Some(junction.#name.clone())
+        }
+    } else {
+        quote! {
+            junction.#name.clone()
+        }
+    }
+}
+
+// `ancestors` and `descendants` field names of self referencing keys, prefixed when a table has several
+pub fn get_hierarchy_relation_names(table: &TableMeta) -> Vec<(&ForeignKeyMeta, String, String)> {
+    let self_references: Vec<&ForeignKeyMeta> = table
+        .foreign_keys
+        .iter()
+        .filter(|fk: &&ForeignKeyMeta| fk.is_self_reference())
+        .collect();
+
+    let prefixed = self_references.len() > 1;
+
+    self_references
+        .into_iter()
+        .map(|fk: &ForeignKeyMeta| {
+            let prefix = if prefixed {
+                format!("{}_", fk.source_columns.iter().map(|name| name.to_snake_case()).collect::<Vec<String>>().join("_"))
+            } else {
+                String::new()
+            };
+
+            (fk, format!("{}ancestors", prefix), format!("{}descendants", prefix))
+        })
+        .collect()
+}
+
+// `ancestors` and `descendants` of self referencing tables, resolved with a single recursive CTE
+pub fn generate_hierarchy_relations(table: &TableMeta) -> Vec<TokenStream> {
+    get_hierarchy_relation_names(table)
+        .into_iter()
+        .flat_map(|(fk, ancestors_name, descendants_name): (&ForeignKeyMeta, String, String)| {
+            let ancestors = generate_hierarchy_query(table, fk, format_ident!("{}", ancestors_name), true);
+            let descendants = generate_hierarchy_query(table, fk, format_ident!("{}", descendants_name), false);
+
+            vec![ancestors, descendants]
+        })
+        .collect()
+}
+
+pub fn generate_hierarchy_query(table: &TableMeta, fk: &ForeignKeyMeta, relation_name: Ident, ancestors: bool) -> TokenStream {
+    let (sql, self_columns) = get_hierarchy_sql(table, fk, ancestors);
+
+    let self_items: Vec<Ident> = self_columns
+        .iter()
+        .map(|name: &String| format_ident!("{}", name.to_snake_case()))
+        .collect();
+
+    quote! {
+        pub async fn #relation_name<'a>(
+            &self,
+            ctx: &Context<'a>,
+            depth: Option<usize>
+        ) -> async_graphql::Result<Vec<Model>> {
+            if let Some(0) = depth {
+                return Ok(vec![]);
+            }
+
+            let db: &DatabaseConnection = ctx.data::<DatabaseConnection>()?;
+
+            let stmt = sea_orm::Statement::from_sql_and_values(
+                sea_orm::ConnectionTrait::get_database_backend(db),
+                #sql,
+                vec![
+                    #(self.#self_items.clone().into(),)*
+                    depth.map(|depth| depth as i64).into(),
+                ]
+            );
+
+            Entity::find()
+                .from_raw_sql(stmt)
+                .all(db)
+                .await
+                .map_err(|error| errors::database_error(&error))
+        }
+    }
+}
+
+// the statement binds the `self_columns` of the current row followed by the optional depth
+pub fn get_hierarchy_sql<'a>(table: &TableMeta, fk: &'a ForeignKeyMeta, ancestors: bool) -> (String, &'a Vec<String>) {
+    let quote_column = |prefix: &str, name: &String| -> String {
+        if prefix.is_empty() {
+            format!("\"{}\"", name)
+        } else {
+            format!("\"{}\".\"{}\"", prefix, name)
+        }
+    };
+
+    let equals = |left: &str, left_columns: &Vec<String>, right: &str, right_columns: &Vec<String>| -> String {
+        left_columns
+            .iter()
+            .zip(right_columns.iter())
+            .map(|(left_column, right_column)| format!("{} = {}", quote_column(left, left_column), quote_column(right, right_column)))
+            .collect::<Vec<String>>()
+            .join(" AND ")
+    };
+
+    let placeholders = |columns: &Vec<String>| -> String {
+        columns
+            .iter()
+            .map(|column| format!("{} = ?", quote_column("", column)))
+            .collect::<Vec<String>>()
+            .join(" AND ")
+    };
+
+    // parents are referenced by `destination_columns`, children point at them with `source_columns`
+    let key_columns = &fk.destination_columns;
+    let parent_columns = &fk.source_columns;
+
+    let hierarchy_columns: Vec<String> = [key_columns.clone(), parent_columns.clone()].concat();
+    let hierarchy_columns_list = hierarchy_columns
+        .iter()
+        .map(|column| quote_column("", column))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let hierarchy_select_list = hierarchy_columns
+        .iter()
+        .map(|column| quote_column("t", column))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let key_columns_list = key_columns
+        .iter()
+        .map(|column| quote_column("", column))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let (anchor_condition, recursive_join, self_columns) = if ancestors {
+        (placeholders(key_columns), equals("t", key_columns, "h", parent_columns), parent_columns)
+    } else {
+        (placeholders(parent_columns), equals("t", parent_columns, "h", key_columns), key_columns)
+    };
+
+    let table_name = quote_column("", &table.table_name);
+
+    let sql = format!(
+        "WITH RECURSIVE \"hierarchy\"({columns}, \"depth\") AS (\
+            SELECT {columns}, 1 FROM {table} WHERE {anchor} \
+            UNION ALL \
+            SELECT {select}, \"h\".\"depth\" + 1 FROM {table} AS \"t\" JOIN \"hierarchy\" AS \"h\" ON {join} \
+            WHERE \"h\".\"depth\" < COALESCE(?, (SELECT COUNT(*) FROM {table}))\
+        ) \
+        SELECT {table}.* FROM {table} \
+        JOIN (SELECT {keys}, MIN(\"depth\") AS \"depth\" FROM \"hierarchy\" GROUP BY {keys}) AS \"h\" ON {key_join} \
+        ORDER BY \"h\".\"depth\"",
+        columns = hierarchy_columns_list,
+        table = table_name,
+        anchor = anchor_condition,
+        select = hierarchy_select_list,
+        join = recursive_join,
+        keys = key_columns_list,
+        key_join = equals(&table.table_name, key_columns, "h", key_columns),
+    );
+
+    (sql, self_columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_hierarchy_relation_names, get_hierarchy_sql};
+    use crate::types::{ForeignKeyMeta, TableMeta};
+    use quote::quote;
+
+    fn self_reference(source_column: &str) -> ForeignKeyMeta {
+        ForeignKeyMeta {
+            source_table_name: "Employees".into(),
+            source_table_module: "employees".into(),
+            source_columns: vec![source_column.into()],
+            source_column_types: vec![quote!(Option<i32>)],
+
+            destination_table_name: "Employees".into(),
+            destination_table_module: "employees".into(),
+            destination_columns: vec!["EmployeeId".into()],
+            destination_column_types: vec![quote!(i32)],
+
+            relation_name: "reports_to_employee".into(),
+            reverse_relation_name: "employees_by_reports_to".into(),
+
+            one_to_one: false,
+
+            declared_relation: None,
+        }
+    }
+
+    fn employees(foreign_keys: Vec<ForeignKeyMeta>) -> TableMeta {
+        TableMeta {
+            table_name: "employees".into(),
+            entity_name: "Employees".into(),
+            entity_module: "employees".into(),
+            filter_name: "EmployeesFilter".into(),
+            paginated_result_name: "PaginatedEmployeesResult".into(),
+            filter_template: "{}Filter".into(),
+            paginated_result_template: "Paginated{}Result".into(),
+            columns: vec![],
+            foreign_keys,
+            many_to_many: vec![],
+        }
+    }
+
+    #[test]
+    fn hierarchy_relations_are_prefixed_when_a_table_has_several() {
+        let single = employees(vec![self_reference("ReportsTo")]);
+        let names: Vec<(String, String)> = get_hierarchy_relation_names(&single)
+            .into_iter()
+            .map(|(_, ancestors, descendants)| (ancestors, descendants))
+            .collect();
+
+        assert_eq!(names, vec![("ancestors".into(), "descendants".into())]);
+
+        let several = employees(vec![self_reference("ReportsTo"), self_reference("MentorId")]);
+        let names: Vec<String> = get_hierarchy_relation_names(&several)
+            .into_iter()
+            .map(|(_, ancestors, _)| ancestors)
+            .collect();
+
+        assert_eq!(names, vec!["reports_to_ancestors".to_string(), "mentor_id_ancestors".to_string()]);
+    }
+
+    #[test]
+    fn ancestors_start_from_the_parent_of_the_row() {
+        let fk = self_reference("ReportsTo");
+        let (sql, self_columns) = get_hierarchy_sql(&employees(vec![]), &fk, true);
+
+        assert_eq!(self_columns, &vec!["ReportsTo".to_string()]);
+        assert!(sql.starts_with("WITH RECURSIVE \"hierarchy\"(\"EmployeeId\", \"ReportsTo\", \"depth\") AS ("));
+        assert!(sql.contains("SELECT \"EmployeeId\", \"ReportsTo\", 1 FROM \"employees\" WHERE \"EmployeeId\" = ?"));
+        assert!(sql.contains("JOIN \"hierarchy\" AS \"h\" ON \"t\".\"EmployeeId\" = \"h\".\"ReportsTo\""));
+        assert!(sql.contains("ON \"employees\".\"EmployeeId\" = \"h\".\"EmployeeId\""));
+    }
+
+    #[test]
+    fn descendants_start_from_the_children_of_the_row() {
+        let fk = self_reference("ReportsTo");
+        let (sql, self_columns) = get_hierarchy_sql(&employees(vec![]), &fk, false);
+
+        assert_eq!(self_columns, &vec!["EmployeeId".to_string()]);
+        assert!(sql.contains("FROM \"employees\" WHERE \"ReportsTo\" = ?"));
+        assert!(sql.contains("JOIN \"hierarchy\" AS \"h\" ON \"t\".\"ReportsTo\" = \"h\".\"EmployeeId\""));
+        // the depth is bound after the columns of the row, the whole table bounds cycles without one
+        assert!(sql.contains("WHERE \"h\".\"depth\" < COALESCE(?, (SELECT COUNT(*) FROM \"employees\"))"));
+        assert!(sql.ends_with("ORDER BY \"h\".\"depth\""));
+    }
+}
@@ -0,0 +1,33 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// error constructors of the generated resolvers, the `code` extension lets clients tell them apart
+pub fn generate_errors() -> TokenStream {
+    quote! {
+        use async_graphql::ErrorExtensions;
+        use sea_orm::DbErr;
+
+        pub const NOT_FOUND: &str = "NOT_FOUND";
+        pub const DATABASE_ERROR: &str = "DATABASE_ERROR";
+        pub const INVALID_FILTER: &str = "INVALID_FILTER";
+
+        pub fn not_found(message: impl Into<String>) -> async_graphql::Error {
+            error(message.into(), NOT_FOUND)
+        }
+
+        pub fn invalid_filter(message: impl Into<String>) -> async_graphql::Error {
+            error(message.into(), INVALID_FILTER)
+        }
+
+        pub fn database_error(db_error: &DbErr) -> async_graphql::Error {
+            match db_error {
+                DbErr::RecordNotFound(message) => error(message.clone(), NOT_FOUND),
+                _ => error(db_error.to_string(), DATABASE_ERROR),
+            }
+        }
+
+        fn error(message: String, code: &'static str) -> async_graphql::Error {
+            async_graphql::Error::new(message).extend_with(|_, extensions| extensions.set("code", code))
+        }
+    }
+}
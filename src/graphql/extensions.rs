@@ -0,0 +1,67 @@
+use crate::types::TableMeta;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+// name of the hidden placeholder field, `Schema::sdl` lists hidden fields so it is filtered there
+pub const EXTENSION_PLACEHOLDER: &str = "_extension";
+
+// header of user-owned files, `quote!` drops comments so it is prepended as text
+pub const EXTENSION_HEADER: &str = "// This file is created once and never overwritten by the generator.\n\
+// Fields added here are merged into the generated GraphQL type.\n\
+// The hidden `_extension` placeholder can be removed once a custom field exists.\n\n";
+
+// header of `mod.rs`, user modules can be declared next to the generated ones
+pub const EXTENSIONS_MOD_HEADER: &str = "// This file is created once and never overwritten by the generator.\n\
+// The modules of new tables are appended, other modules can be added freely.\n\n";
+
+pub fn generate_entity_extension(table: &TableMeta) -> TokenStream {
+    let entity_module: Ident = format_ident!("{}", table.entity_module);
+    let extension_name = format!("{}Extension", table.entity_name);
+
+    quote! {
+        use async_graphql::Context;
+
+        use crate::orm::#entity_module::Model;
+
+        pub struct ModelExtension<'a>(pub &'a Model);
+
+        #[async_graphql::Object(name = #extension_name, visible = false)]
+        impl<'a> ModelExtension<'a> {
+            #[graphql(name = "_extension", visible = false)]
+            async fn _extension<'ctx>(&self, _ctx: &Context<'ctx>) -> bool {
+                true
+            }
+        }
+    }
+}
+
+pub fn generate_query_extension() -> TokenStream {
+    quote! {
+        use async_graphql::Context;
+
+        #[derive(Default)]
+        pub struct QueryExtension;
+
+        #[async_graphql::Object(visible = false)]
+        impl QueryExtension {
+            #[graphql(name = "_extension", visible = false)]
+            async fn _extension<'ctx>(&self, _ctx: &Context<'ctx>) -> bool {
+                true
+            }
+        }
+    }
+}
+
+pub fn generate_extensions_mod(tables_meta: &[TableMeta]) -> TokenStream {
+    let entity_modules: Vec<Ident> = tables_meta
+        .iter()
+        .map(|table: &TableMeta| format_ident!("{}", table.entity_module))
+        .collect();
+
+    quote! {
+        #(pub mod #entity_modules;)*
+        pub mod query;
+
+        pub use query::QueryExtension;
+    }
+}
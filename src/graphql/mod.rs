@@ -1,35 +1,30 @@
 pub mod entities;
+pub mod errors;
+pub mod extensions;
+pub mod introspection;
 pub mod type_filter;
 pub mod root_node;
 pub mod orm_data_loader;
 
 use quote::{quote, format_ident};
-use crate::types::TableMeta;
+use crate::{
+    error::Result,
+    formatter::format_tokens,
+    plugin::{merge_plugin_modules, Plugins},
+    types::TableMeta,
+};
 
 use entities::generate_graphql_entities;
 use type_filter::generate_type_filter;
 use root_node::generate_root;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, TokenStream};
+use std::collections::BTreeMap;
 
-pub fn write_type_filter(dir: &String)
-{
-    let dir = std::path::Path::new(dir);
+// every file below the graphql directory, keyed by its relative path
+pub fn generate_graphql(tables_meta: &[TableMeta], plugins: &Plugins) -> Result<BTreeMap<String, TokenStream>> {
+    let mut files: BTreeMap<String, TokenStream> = BTreeMap::new();
 
-    let type_filter = generate_type_filter();
-
-    std::fs::write(dir.join("type_filter.rs"), type_filter.to_string()).unwrap();
-}
-
-pub fn write_entities(dir: &String, tables_meta: &Vec<TableMeta>) {
-    let dir = std::path::Path::new(dir);
-
-    std::fs::create_dir_all(dir).unwrap();
-
-    let entities = generate_graphql_entities(tables_meta);
-
-    for (name, entity) in entities.iter() {
-        std::fs::write(dir.join(format!("{}.rs", name)), entity.to_string()).unwrap();
-    }
+    let entities = generate_graphql_entities(tables_meta, plugins);
 
     let entity_names: Vec<Ident> = entities
         .keys()
@@ -38,46 +33,121 @@ pub fn write_entities(dir: &String, tables_meta: &Vec<TableMeta>) {
         })
         .collect();
 
-    let mod_tokens = quote!{
+    for (name, entity) in entities.into_iter() {
+        files.insert(format!("entities/{}.rs", name), entity);
+    }
+
+    files.insert("entities/mod.rs".into(), quote!{
         #(pub mod #entity_names;)*
-    };
+    });
 
-    std::fs::write(dir.join("mod.rs"), mod_tokens.to_string()).unwrap();
-}
+    let modules = generate_graphql_modules(tables_meta, plugins)?;
 
-pub fn write_root_node(dir: &String, tables_meta: &Vec<TableMeta>) {
-    let tokens = generate_root(tables_meta);
+    let module_names: Vec<Ident> = modules
+        .keys()
+        .map(|name: &String| format_ident!("{}", name))
+        .collect();
+
+    for (name, module) in modules.into_iter() {
+        files.insert(format!("{}.rs", name), module);
+    }
+
+    let exports = generate_graphql_exports();
 
-    let dir = std::path::Path::new(dir);
-    std::fs::write(dir.join("query_root.rs"), tokens.to_string()).unwrap();
+    files.insert("mod.rs".into(), quote!{
+        pub mod entities;
+        #(pub mod #module_names;)*
+        #exports
+    });
+
+    Ok(files)
 }
 
-pub fn write_orm_data_loader(dir: &String) {
-    let tokens = orm_data_loader::generate_orm_data_loader();
+// the same code as a single `graphql` module, for `include!` from a build script
+pub fn generate_graphql_module(tables_meta: &[TableMeta], plugins: &Plugins) -> Result<TokenStream> {
+    let entities: Vec<TokenStream> = generate_graphql_entities(tables_meta, plugins)
+        .into_iter()
+        .map(|(name, entity)| {
+            let name = format_ident!("{}", name);
+
+            quote! {
+                pub mod #name {
+                    #entity
+                }
+            }
+        })
+        .collect();
+
+    let modules: Vec<TokenStream> = generate_graphql_modules(tables_meta, plugins)?
+        .into_iter()
+        .map(|(name, module)| {
+            let name = format_ident!("{}", name);
 
-    let dir = std::path::Path::new(dir);
-    std::fs::write(dir.join("orm_data_loader.rs"), tokens.to_string()).unwrap();
+            quote! {
+                pub mod #name {
+                    #module
+                }
+            }
+        })
+        .collect();
+
+    let exports = generate_graphql_exports();
+
+    Ok(quote! {
+        pub mod graphql {
+            pub mod entities {
+                #(#entities)*
+            }
+
+            #(#modules)*
+
+            #exports
+        }
+    })
 }
 
-pub fn write_graphql(dir: &String, tables_meta: &Vec<TableMeta>) {
-    write_entities(&format!("{}/entities", dir), tables_meta);
+// modules next to `entities`, keyed by module name
+fn generate_graphql_modules(tables_meta: &[TableMeta], plugins: &Plugins) -> Result<BTreeMap<String, TokenStream>> {
+    let mut modules: BTreeMap<String, TokenStream> = BTreeMap::new();
 
-    write_type_filter(dir);
+    modules.insert("type_filter".into(), generate_type_filter());
 
+    modules.insert("query_root".into(), generate_root(tables_meta, plugins));
 
-    write_root_node(dir, tables_meta);
+    modules.insert("orm_data_loader".into(), orm_data_loader::generate_orm_data_loader());
 
-    write_orm_data_loader(dir);
+    modules.insert("errors".into(), errors::generate_errors());
 
-    let mod_tokens = quote!{
-        pub mod entities;
-        pub mod query_root;
-        pub mod type_filter;
-        pub mod orm_data_loader;
-        pub use query_root::QueryRoot;
+    merge_plugin_modules(plugins, tables_meta, modules)
+}
+
+fn generate_graphql_exports() -> TokenStream {
+    quote! {
+        pub use query_root::{Query, QueryRoot};
         pub use type_filter::TypeFilter;
         pub use orm_data_loader::OrmDataLoader;
-    };
+    }
+}
+
+// user owned files below `src/extensions`, they are only created when missing
+// `mod.rs` lists the entities and is generated with the project, see `generate_extensions_mod`
+pub fn generate_extensions(tables_meta: &[TableMeta]) -> Result<BTreeMap<String, String>> {
+    let mut files: BTreeMap<String, String> = BTreeMap::new();
+
+    for table in tables_meta.iter() {
+        let name = format!("{}.rs", table.entity_module);
+        let content = format_tokens(&name, &extensions::generate_entity_extension(table))?;
+
+        files.insert(name, format!("{}{}", extensions::EXTENSION_HEADER, content));
+    }
 
-    std::fs::write(std::path::Path::new(dir).join("mod.rs"), mod_tokens.to_string()).unwrap();
-}
\ No newline at end of file
+    let content = format_tokens("query.rs", &extensions::generate_query_extension())?;
+
+    files.insert("query.rs".into(), format!("{}{}", extensions::EXTENSION_HEADER, content));
+
+    let content = format_tokens("mod.rs", &extensions::generate_extensions_mod(tables_meta))?;
+
+    files.insert("mod.rs".into(), format!("{}{}", extensions::EXTENSIONS_MOD_HEADER, content));
+
+    Ok(files)
+}
@@ -1,16 +1,23 @@
-use crate::types::{ColumnMeta, TableMeta};
+use crate::{
+    plugin::Plugins,
+    types::{ColumnMeta, TableMeta},
+};
+use heck::ToLowerCamelCase;
 use proc_macro2::{TokenStream};
 use quote::{format_ident, quote};
 
-pub fn generate_root(tables_meta: &Vec<TableMeta>) -> TokenStream {
+pub fn generate_root(tables_meta: &[TableMeta], plugins: &Plugins) -> TokenStream {
     let pagination_input = generate_pagination_input();
 
     let paginated_result = generate_paginated_result(tables_meta);
 
     let single_queries: Vec<TokenStream> = generate_single_queries(tables_meta);
 
+    let plugin_resolvers: Vec<TokenStream> = plugins.iter().flat_map(|plugin| plugin.root_resolvers(tables_meta)).collect();
+
     quote! {
         use super::entities;
+        use super::errors;
 
         use async_graphql::Context;
         use sea_orm::prelude::*;
@@ -19,16 +26,21 @@ pub fn generate_root(tables_meta: &Vec<TableMeta>) -> TokenStream {
 
         #paginated_result
 
+        #[derive(Default)]
         pub struct QueryRoot;
 
+        #[derive(async_graphql::MergedObject, Default)]
+        pub struct Query(QueryRoot, crate::extensions::QueryExtension);
+
         #[async_graphql::Object]
         impl QueryRoot {
             #(#single_queries)*
+            #(#plugin_resolvers)*
         }
     }
 }
 
-pub fn generate_single_queries(tables_meta: &Vec<TableMeta>) -> Vec<TokenStream> {
+pub fn generate_single_queries(tables_meta: &[TableMeta]) -> Vec<TokenStream> {
     tables_meta
         .iter()
         .map(|table: &TableMeta| {
@@ -41,46 +53,48 @@ pub fn generate_single_queries(tables_meta: &Vec<TableMeta>) -> Vec<TokenStream>
                     &self, ctx: &Context<'a>,
                     filters: Option<entities::#entity_module::Filter>,
                     pagination: Option<PaginationInput>,
-                ) -> PaginatedResult<entities::#entity_module::Model> {
-                    println!("filters: {:?}", filters);
-
+                ) -> async_graphql::Result<PaginatedResult<entities::#entity_module::Model>> {
                     #filter_recursive
 
-                    let db: &DatabaseConnection = ctx.data::<DatabaseConnection>().unwrap();
+                    let db: &DatabaseConnection = ctx.data::<DatabaseConnection>()?;
 
                     let stmt = entities::#entity_module::Entity::find()
-                        .filter(filter_recursive(filters));
+                        .filter(filter_recursive(filters)?);
 
                     if let Some(pagination) = pagination {
+                        if pagination.limit == 0 {
+                            return Err(errors::invalid_filter("pagination limit must be greater than 0"));
+                        }
+
                         let paginator = stmt
                             .paginate(db, pagination.limit);
 
                         let data: Vec<entities::#entity_module::Model> = paginator
                             .fetch_page(pagination.page)
                             .await
-                            .unwrap();
+                            .map_err(|error| errors::database_error(&error))?;
 
                         let pages = paginator
                             .num_pages()
                             .await
-                            .unwrap();
+                            .map_err(|error| errors::database_error(&error))?;
 
-                        PaginatedResult {
+                        Ok(PaginatedResult {
                             data,
                             pages,
                             current: pagination.page
-                        }
+                        })
                     } else {
                         let data: Vec<entities::#entity_module::Model> = stmt
                             .all(db)
                             .await
-                            .unwrap();
+                            .map_err(|error| errors::database_error(&error))?;
 
-                        PaginatedResult {
+                        Ok(PaginatedResult {
                             data,
                             pages: 1,
                             current: 1
-                        }
+                        })
                     }
                 }
             }
@@ -96,10 +110,14 @@ pub fn generate_recursive_filter_fn(table_meta: &TableMeta) -> TokenStream {
         .iter()
         .map(|column: &ColumnMeta| {
             let column_name = format_ident!("{}", column.column_name);
+            let field_name = format_ident!("{}", column.field_name);
             let column_enum_name = format_ident!("{}", column.column_enum_name);
+            let graphql_name = column.field_name.to_lower_camel_case();
+            let is_in_message = format!("`isIn` of `{}` must not be empty", graphql_name);
+            let is_not_in_message = format!("`isNotIn` of `{}` must not be empty", graphql_name);
 
             quote! {
-                if let Some(#column_name) = current_filter.#column_name {
+                if let Some(#column_name) = current_filter.#field_name {
                     if let Some(eq_value) = #column_name.eq {
                         condition = condition.add(entities::#entity_module::Column::#column_enum_name.eq(eq_value))
                     }
@@ -107,40 +125,89 @@ pub fn generate_recursive_filter_fn(table_meta: &TableMeta) -> TokenStream {
                     if let Some(ne_value) = #column_name.ne {
                         condition = condition.add(entities::#entity_module::Column::#column_enum_name.ne(ne_value))
                     }
+
+                    if let Some(gt_value) = #column_name.gt {
+                        condition = condition.add(entities::#entity_module::Column::#column_enum_name.gt(gt_value))
+                    }
+
+                    if let Some(gte_value) = #column_name.gte {
+                        condition = condition.add(entities::#entity_module::Column::#column_enum_name.gte(gte_value))
+                    }
+
+                    if let Some(lt_value) = #column_name.lt {
+                        condition = condition.add(entities::#entity_module::Column::#column_enum_name.lt(lt_value))
+                    }
+
+                    if let Some(lte_value) = #column_name.lte {
+                        condition = condition.add(entities::#entity_module::Column::#column_enum_name.lte(lte_value))
+                    }
+
+                    if let Some(is_in_value) = #column_name.is_in {
+                        if is_in_value.is_empty() {
+                            return Err(errors::invalid_filter(#is_in_message));
+                        }
+
+                        condition = condition.add(entities::#entity_module::Column::#column_enum_name.is_in(is_in_value))
+                    }
+
+                    if let Some(is_not_in_value) = #column_name.is_not_in {
+                        if is_not_in_value.is_empty() {
+                            return Err(errors::invalid_filter(#is_not_in_message));
+                        }
+
+                        condition = condition.add(entities::#entity_module::Column::#column_enum_name.is_not_in(is_not_in_value))
+                    }
+
+                    if let Some(is_null_value) = #column_name.is_null {
+                        if is_null_value {
+                            condition = condition.add(entities::#entity_module::Column::#column_enum_name.is_null())
+                        } else {
+                            condition = condition.add(entities::#entity_module::Column::#column_enum_name.is_not_null())
+                        }
+                    }
                 }
             }
         })
         .collect();
 
     quote! {
-        fn filter_recursive(root_filter: Option<entities::#entity_module::Filter>) -> sea_orm::Condition {
+        // empty lists cannot be applied, `isIn: []` would silently match nothing
+        fn filter_recursive(root_filter: Option<entities::#entity_module::Filter>) -> async_graphql::Result<sea_orm::Condition> {
             let mut condition = sea_orm::Condition::all();
 
             if let Some(current_filter) = root_filter {
                 if let Some(or_filters) = current_filter.or {
+                    if or_filters.is_empty() {
+                        return Err(errors::invalid_filter("`or` must not be empty"));
+                    }
+
                     let or_condition = or_filters
                         .into_iter()
-                        .fold(
+                        .try_fold(
                             sea_orm::Condition::any(),
-                            |fold_condition, filter| fold_condition.add(filter_recursive(Some(*filter)))
-                        );
+                            |fold_condition, filter| filter_recursive(Some(*filter)).map(|nested| fold_condition.add(nested))
+                        )?;
                     condition = condition.add(or_condition);
                 }
 
                 if let Some(and_filters) = current_filter.and {
+                    if and_filters.is_empty() {
+                        return Err(errors::invalid_filter("`and` must not be empty"));
+                    }
+
                     let and_condition = and_filters
                         .into_iter()
-                        .fold(
+                        .try_fold(
                             sea_orm::Condition::all(),
-                            |fold_condition, filter| fold_condition.add(filter_recursive(Some(*filter)))
-                        );
+                            |fold_condition, filter| filter_recursive(Some(*filter)).map(|nested| fold_condition.add(nested))
+                        )?;
                     condition = condition.add(and_condition);
                 }
 
                 #(#columns_filters)*
             }
 
-            condition
+            Ok(condition)
         }
     }
 }
@@ -155,11 +222,11 @@ pub fn generate_pagination_input() -> TokenStream {
     }
 }
 
-pub fn generate_paginated_result(tables_meta: &Vec<TableMeta>) -> TokenStream {
+pub fn generate_paginated_result(tables_meta: &[TableMeta]) -> TokenStream {
     let derives: Vec<TokenStream> = tables_meta
         .iter()
         .map(|table_meta: &TableMeta| {
-            let name = format!("Paginated{}Result", table_meta.entity_name);
+            let name = table_meta.paginated_result_name.clone();
             let module = format_ident!("{}", table_meta.entity_module);
             quote!{
                 #[graphql(concrete(name = #name, params(entities::#module::Model)))]
@@ -1,6 +1,27 @@
+pub mod build_script;
+pub mod client_generator;
 pub mod column_mapping;
+pub mod config;
 pub mod database_schema;
+#[cfg(feature = "serve")]
+pub mod dynamic_schema;
 pub mod entities_generator;
+pub mod error;
+pub mod formatter;
+pub mod generator;
+pub mod operations_generator;
+pub mod plugin;
+pub mod scalar_mapping;
 pub mod toml_generator;
+pub mod typescript_generator;
 pub mod types;
-pub mod graphql;
\ No newline at end of file
+pub mod graphql;
+pub mod project_generator;
+pub mod schema_report;
+pub mod schema_watcher;
+pub mod settings_generator;
+pub mod sdl_diff;
+
+pub use error::GeneratorError;
+pub use generator::{DirectorySink, GeneratedProject, Generator, OutputSink, SchemaSource};
+pub use plugin::GeneratorPlugin;
@@ -1,109 +1,311 @@
-use quote::{format_ident, quote};
 use rust_graphql_generator_demo::{
-    database_schema::get_database_schema, entities_generator::generate_entities,
-    graphql::write_graphql, toml_generator::write_toml, types::TableMeta
+    config::{GeneratorConfig, CONFIG_FILE_NAME},
+    entities_generator::generate_entities_files,
+    error::{GeneratorError, Result},
+    generator::{DirectorySink, GeneratedProject, Generator, OutputSink, SchemaSource},
+    project_generator::{check_project, generate_sdl},
+    schema_report::compare_schemas, schema_watcher::get_schema_version, sdl_diff::compare_sdl,
+    types::{SchemaSnapshot, TableMeta}
 };
-use sea_schema::sea_query::table::TableCreateStatement;
 use sqlx::SqlitePool;
-use std::{env, fs, path, process};
+use std::{env, fs, path, process::ExitCode, time::Duration};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Generate,
+    // compares the generated code with the existing project instead of writing it
+    Check,
+    // keeps running and regenerates the project whenever the schema changes
+    Watch,
+    // compares the GraphQL schema of the existing project with the one generated now
+    SchemaCheck,
+    // stores the schema, `report` compares it with a snapshot
+    Snapshot,
+    Report,
+    // exposes the database through a schema built at runtime, nothing is generated
+    Serve,
+}
+
+const GENERATE_USAGE: &str = "[<project>] [--infer-relations]";
+
+// name, command, usage and the range of operands it takes
+const COMMANDS: [(&str, Command, &str, usize, usize); 6] = [
+    ("check", Command::Check, "check [<project>]", 0, 1),
+    ("watch", Command::Watch, "watch [<project>]", 0, 1),
+    ("schema-check", Command::SchemaCheck, "schema-check [<project>]", 0, 1),
+    ("snapshot", Command::Snapshot, "snapshot <file>", 1, 1),
+    ("report", Command::Report, "report <previous> [<current>] [--json]", 1, 2),
+    ("serve", Command::Serve, "serve [<address>]", 0, 1),
+];
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(exit_code) => exit_code,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// a failed check is reported through the exit code, everything else through `GeneratorError`
+async fn run() -> Result<ExitCode> {
     // TODO proper CLI application
-    let arguments: Vec<String> = env::args().collect();
+    let arguments: Vec<String> = env::args().skip(1).collect();
 
-    let default_project_name: String = "generated".into();
-    let project_name: &String = arguments.get(1).unwrap_or(&default_project_name);
-    let project_dir = path::Path::new(project_name);
+    let (flags, positional_arguments): (Vec<&String>, Vec<&String>) =
+        arguments.iter().partition(|argument| argument.starts_with("--"));
 
-    let connection = SqlitePool::connect("sqlite://chinook.db").await.unwrap();
+    // the first operand names the command, without one the only operand is the project
+    let command_entry = positional_arguments
+        .first()
+        .and_then(|name| COMMANDS.iter().find(|(command_name, ..)| name.as_str().eq(*command_name)));
 
-    let (tables_meta, table_create_stmts): (Vec<TableMeta>, Vec<TableCreateStatement>) = get_database_schema(connection).await;
+    let (command, usage, operands): (Command, &str, &[&String]) = match command_entry {
+        Some((_, command, usage, min, max)) => {
+            let operands = &positional_arguments[1..];
 
-    {
-        let folder: String = format!("{}/src/orm", project_name).into();
-        let dir = path::Path::new(&folder);
-        fs::create_dir_all(dir).unwrap();
-        generate_entities(dir, table_create_stmts).unwrap();
+            if operands.len() < *min || operands.len() > *max {
+                return Err(GeneratorError::Usage((*usage).into()));
+            }
+
+            (*command, usage, operands)
+        }
+        None if positional_arguments.len() > 1 => return Err(GeneratorError::Usage(GENERATE_USAGE.into())),
+        None => (Command::Generate, GENERATE_USAGE, &positional_arguments[..]),
+    };
+
+    // commands cannot be combined, e.g. `check watch`
+    if operands.iter().any(|operand| COMMANDS.iter().any(|(command_name, ..)| operand.as_str().eq(*command_name))) {
+        return Err(GeneratorError::Usage(usage.into()));
     }
 
-    write_toml(project_dir, &"generated".into()).unwrap();
+    for flag in flags.iter() {
+        let known = flag.as_str().eq("--infer-relations") || (flag.as_str().eq("--json") && command.eq(&Command::Report));
 
-    {
-        let dir: String = format!("{}/src/graphql", project_name).into();
-        fs::create_dir_all(&dir).unwrap();
-        write_graphql(&dir, &tables_meta);
+        if !known {
+            return Err(GeneratorError::Usage(usage.into()));
+        }
     }
 
-    let lib_tokens = quote! {
-        pub mod orm;
-        pub mod graphql;
+    let mut config = GeneratorConfig::load(path::Path::new(CONFIG_FILE_NAME))?;
 
-        pub use graphql::QueryRoot;
-        pub use graphql::OrmDataLoader;
+    if flags.iter().any(|flag| flag.as_str().eq("--infer-relations")) {
+        config.infer_relations = true;
+    }
+
+    // the operands of `snapshot`, `report` and `serve` are files and addresses, not the project
+    let project_name: &str = match (command, operands.first()) {
+        (Command::Snapshot | Command::Report | Command::Serve, _) | (_, None) => "generated",
+        (_, Some(project_name)) => project_name,
     };
-    fs::write(
-        format!("{}/src/lib.rs", project_name),
-        lib_tokens.to_string(),
-    )
-    .unwrap();
-
-    let crate_name = format_ident!("{}", project_name);
-    let main_tokens = quote! {
-        use async_graphql::{
-            http::{playground_source, GraphQLPlaygroundConfig},
-            EmptyMutation, EmptySubscription, Schema, dataloader::DataLoader
+    let project_dir = path::Path::new(project_name);
+    // the package is named after the directory, `../services/api` is package `api`
+    let package_name: &str = project_dir
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or(project_name);
+
+    let connection = SqlitePool::connect("sqlite://chinook.db")
+        .await
+        .map_err(GeneratorError::Connection)?;
+
+    let generator = Generator::new(SchemaSource::Connection(connection.clone()))
+        .project_name(package_name)
+        .config(config.clone());
+
+    let (tables_meta, table_create_stmts, inferred_foreign_keys) = generator.load_schema().await?;
+
+    for fk in inferred_foreign_keys.iter() {
+        println!(
+            "Inferred relation: {}({}) -> {}({})",
+            fk.source_table_name,
+            fk.source_columns.join(", "),
+            fk.destination_table_name,
+            fk.destination_columns.join(", ")
+        );
+    }
+
+    if command.eq(&Command::Snapshot) {
+        let snapshot_path = path::Path::new(operands[0]);
+
+        let snapshot = SchemaSnapshot::new(&tables_meta).with_orm_files(generate_entities_files(table_create_stmts)?);
+
+        fs::write(snapshot_path, snapshot.to_json()?)?;
+
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if command.eq(&Command::Report) {
+        let previous = SchemaSnapshot::load(path::Path::new(operands[0]))?;
+
+        let current = match operands.get(1) {
+            Some(current_path) => SchemaSnapshot::load(path::Path::new(current_path))?,
+            None => SchemaSnapshot::new(&tables_meta),
         };
-        use async_graphql_poem::GraphQL;
-        use poem::{get, handler, listener::TcpListener, web::Html, IntoResponse, Route, Server};
-        use sea_orm::Database;
 
-        use #crate_name::*;
+        let schema_report = compare_schemas(&previous, &current);
 
-        #[handler]
-        async fn graphql_playground() -> impl IntoResponse {
-            Html(playground_source(GraphQLPlaygroundConfig::new("/")))
+        if flags.iter().any(|flag| flag.as_str().eq("--json")) {
+            println!("{}", schema_report.to_json()?);
+        } else if schema_report.is_empty() {
+            println!("No schema changes");
+        } else {
+            print!("{}", schema_report);
         }
 
-        #[tokio::main]
-        async fn main() {
-            tracing_subscriber::fmt()
-                .with_max_level(tracing::Level::DEBUG)
-                .with_test_writer()
-                .init();
+        return Ok(ExitCode::SUCCESS);
+    }
 
-            let database = Database::connect("sqlite://../chinook.db").await.unwrap();
+    if command.eq(&Command::Serve) {
+        let address = operands.first().map(|address| address.as_str()).unwrap_or("0.0.0.0:8000");
 
-            let orm_data_loader: DataLoader<OrmDataLoader> = DataLoader::new(
-                OrmDataLoader {
-                    db: database.clone()
-                },
-                tokio::spawn
-            ) ;
+        serve_schema(&tables_meta, connection, address).await?;
 
-            let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
-                .data(database)
-                .data(orm_data_loader)
-                .finish();
+        return Ok(ExitCode::SUCCESS);
+    }
 
-            let app = Route::new().at("/", get(graphql_playground).post(GraphQL::new(schema)));
+    let project: GeneratedProject = generator.generate_from_schema(tables_meta, table_create_stmts, inferred_foreign_keys)?;
 
-            println!("Playground: http://localhost:8000");
+    if command.eq(&Command::SchemaCheck) {
+        let sdl_path = project_dir.join("schema.graphql");
 
-            Server::new(TcpListener::bind("0.0.0.0:8000"))
-                .run(app)
-                .await
-                .unwrap();
+        if !sdl_path.exists() {
+            return Err(GeneratorError::MissingSdl(sdl_path.display().to_string()));
         }
 
-    };
-    fs::write(
-        format!("{}/src/main.rs", project_name),
-        main_tokens.to_string(),
-    )
-    .unwrap();
+        let previous_sdl = fs::read_to_string(sdl_path)?;
+
+        let current_sdl = generate_sdl(project_dir, &project.files, config.web_framework)?;
+
+        let changes = compare_sdl(&previous_sdl, &current_sdl)?;
+
+        let mut blocking_changes: usize = 0;
+
+        for change in changes.iter() {
+            if change.breaking && config.is_breaking_change_allowed(&change.coordinate) {
+                println!("{} (allowed)", change);
+            } else {
+                if change.breaking {
+                    blocking_changes += 1;
+                }
+
+                println!("{}", change);
+            }
+        }
+
+        if blocking_changes > 0 {
+            println!("{} breaking schema changes", blocking_changes);
+            return Ok(ExitCode::FAILURE);
+        }
+
+        if changes.is_empty() {
+            println!("No schema changes");
+        }
+
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if command.eq(&Command::Check) {
+        let differences = check_project(project_dir, &project.files)?;
+
+        if differences.is_empty() {
+            println!("{} is up to date", project_name);
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        for difference in differences.iter() {
+            println!("{}", difference);
+        }
+
+        return Ok(ExitCode::FAILURE);
+    }
+
+    DirectorySink::new(project_dir).write(&project)?;
+
+    if command.ne(&Command::Watch) {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    println!("Watching for schema changes, press Ctrl+C to stop");
+
+    let mut schema_version: i64 = get_schema_version(&connection).await?;
+    let mut previous_tables_meta: Vec<TableMeta> = project.tables_meta;
+
+    // errors are printed and the watcher keeps polling, e.g. while a migration is half applied
+    // the version only moves on once the project is regenerated, a failure is retried on the next poll
+    let mut last_error: Option<String> = None;
+
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        let current_schema_version: i64 = match get_schema_version(&connection).await {
+            Ok(current_schema_version) => current_schema_version,
+            Err(error) => {
+                report_watch_error(&mut last_error, error);
+                continue;
+            }
+        };
+
+        if current_schema_version == schema_version {
+            continue;
+        }
+
+        let project: GeneratedProject = match generator.generate().await {
+            Ok(project) => project,
+            Err(error) => {
+                report_watch_error(&mut last_error, error);
+                continue;
+            }
+        };
+
+        let schema_report = compare_schemas(
+            &SchemaSnapshot::new(&previous_tables_meta),
+            &SchemaSnapshot::new(&project.tables_meta),
+        );
+
+        // indexes and other changes that do not affect the generated code
+        if !schema_report.is_empty() {
+            if let Err(error) = DirectorySink::new(project_dir).write(&project) {
+                report_watch_error(&mut last_error, error);
+                continue;
+            }
+
+            print!("{}", schema_report);
+            println!("Regenerated {}", project_name);
+        }
+
+        schema_version = current_schema_version;
+        previous_tables_meta = project.tables_meta;
+        last_error = None;
+    }
+}
+
+// the same error is printed once while it repeats on every poll
+fn report_watch_error(last_error: &mut Option<String>, error: GeneratorError) {
+    let message = error.to_string();
+
+    if last_error.as_ref().ne(&Some(&message)) {
+        eprintln!("error: {}", message);
+        *last_error = Some(message);
+    }
+}
+
+#[cfg(feature = "serve")]
+async fn serve_schema(tables_meta: &[TableMeta], connection: SqlitePool, address: &str) -> Result<()> {
+    use rust_graphql_generator_demo::dynamic_schema::{build_dynamic_schema, serve_dynamic_schema};
+
+    let schema = build_dynamic_schema(tables_meta, connection)?;
 
-    env::set_current_dir(project_dir).unwrap();
+    println!("Playground: http://{}", address);
+
+    serve_dynamic_schema(schema, address).await
+}
 
-    process::Command::new("cargo").arg("fmt").output().unwrap();
+#[cfg(not(feature = "serve"))]
+async fn serve_schema(_tables_meta: &[TableMeta], _connection: SqlitePool, _address: &str) -> Result<()> {
+    Err(GeneratorError::Usage("serve [<address>], with the generator built with the `serve` feature".into()))
 }
@@ -0,0 +1,161 @@
+use crate::{
+    error::{GeneratorError, Result},
+    graphql::entities::{get_hierarchy_relation_names, get_relation_directions},
+    scalar_mapping::get_scalar,
+    types::{ColumnMeta, ManyToManyMeta, TableMeta},
+};
+use heck::{ToLowerCamelCase, ToUpperCamelCase};
+use std::collections::BTreeMap;
+
+const HEADER: &str = "# Generated from the database schema, do not edit.\n";
+
+// one document per operation, keyed by `<entity module>/<operation>.graphql`
+// TODO mutation documents once the server exposes mutations
+pub fn generate_operations(tables_meta: &[TableMeta]) -> Result<BTreeMap<String, String>> {
+    let mut files: BTreeMap<String, String> = BTreeMap::new();
+
+    for table in tables_meta.iter() {
+        files.insert(
+            format!("{}/list.graphql", table.entity_module),
+            generate_list_operation(table),
+        );
+
+        if let Some(document) = generate_by_primary_key_operation(table) {
+            files.insert(format!("{}/by_primary_key.graphql", table.entity_module), document);
+        }
+
+        // a directory of their own, a relation named `list` cannot replace `list.graphql`
+        for (relation_name, document) in generate_relation_operations(table, tables_meta)?.into_iter() {
+            files.insert(format!("{}/relations/{}.graphql", table.entity_module, relation_name), document);
+        }
+    }
+
+    Ok(files)
+}
+
+fn get_fields(table: &TableMeta, indent: usize) -> String {
+    table
+        .columns
+        .iter()
+        .map(|column: &ColumnMeta| format!("{}{}", " ".repeat(indent), column.field_name.to_lower_camel_case()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// `query <name>(<variables>) { <entity>(<arguments>) { data { <fields> } pages current } }`
+fn generate_query(name: &str, variables: &str, table: &TableMeta, arguments: &str, fields: &str) -> String {
+    format!(
+        "{}\nquery {}({}) {{\n  {}({}) {{\n    data {{\n{}\n    }}\n    pages\n    current\n  }}\n}}\n",
+        HEADER,
+        name,
+        variables,
+        table.entity_module.to_lower_camel_case(),
+        arguments,
+        fields
+    )
+}
+
+fn generate_list_operation(table: &TableMeta) -> String {
+    generate_query(
+        &format!("{}List", table.entity_name),
+        &format!("$filters: {}, $pagination: PaginationInput", table.filter_name),
+        table,
+        "filters: $filters, pagination: $pagination",
+        &get_fields(table, 6),
+    )
+}
+
+// the root query only filters, the primary key columns are matched with `eq`
+fn generate_by_primary_key_operation(table: &TableMeta) -> Option<String> {
+    let primary_key: Vec<&ColumnMeta> = table
+        .columns
+        .iter()
+        .filter(|column: &&ColumnMeta| column.is_primary_key)
+        .collect();
+
+    if primary_key.is_empty() {
+        return None;
+    }
+
+    let variables: Vec<String> = primary_key
+        .iter()
+        .map(|column: &&ColumnMeta| {
+            let (scalar, _) = get_scalar(&column.column_filter_type.to_string());
+
+            format!("${}: {}!", column.field_name.to_lower_camel_case(), scalar)
+        })
+        .collect();
+
+    let filters: Vec<String> = primary_key
+        .iter()
+        .map(|column: &&ColumnMeta| {
+            let field_name = column.field_name.to_lower_camel_case();
+
+            format!("{}: {{ eq: ${} }}", field_name, field_name)
+        })
+        .collect();
+
+    Some(generate_query(
+        &format!("{}ByPrimaryKey", table.entity_name),
+        &variables.join(", "),
+        table,
+        &format!("filters: {{ {} }}", filters.join(", ")),
+        &get_fields(table, 6),
+    ))
+}
+
+// every relation field with the columns of the related entity
+// a plugin can leave out the table a relation points to
+fn generate_relation_operations(table: &TableMeta, tables_meta: &[TableMeta]) -> Result<Vec<(String, String)>> {
+    let find_table = |entity_name: &String| -> Result<&TableMeta> {
+        tables_meta
+            .iter()
+            .find(|table: &&TableMeta| table.entity_name.eq(entity_name))
+            .ok_or_else(|| GeneratorError::MissingTable(entity_name.clone()))
+    };
+
+    // relation field, its arguments, extra variables and the related table
+    let mut relations: Vec<(String, &str, &str, &TableMeta)> = Vec::new();
+
+    for (fk, reverse) in get_relation_directions(table).into_iter() {
+        let destination = if reverse { &fk.source_table_name } else { &fk.destination_table_name };
+
+        relations.push((fk.get_relation_name(reverse).clone(), "", "", find_table(destination)?));
+    }
+
+    for relation in table.many_to_many.iter() {
+        let ManyToManyMeta { relation_name, destination_foreign_key, .. } = relation;
+
+        relations.push((relation_name.clone(), "", "", find_table(&destination_foreign_key.destination_table_name)?));
+    }
+
+    for (_, ancestors, descendants) in get_hierarchy_relation_names(table).into_iter() {
+        relations.push((ancestors, "(depth: $depth)", ", $depth: Int", table));
+        relations.push((descendants, "(depth: $depth)", ", $depth: Int", table));
+    }
+
+    let operations = relations
+        .into_iter()
+        .map(|(relation_name, arguments, variables, destination)| {
+            let fields = format!(
+                "{}\n      {}{} {{\n{}\n      }}",
+                get_fields(table, 6),
+                relation_name.to_lower_camel_case(),
+                arguments,
+                get_fields(destination, 8)
+            );
+
+            let document = generate_query(
+                &format!("{}{}", table.entity_name, relation_name.to_upper_camel_case()),
+                &format!("$filters: {}, $pagination: PaginationInput{}", table.filter_name, variables),
+                table,
+                "filters: $filters, pagination: $pagination",
+                &fields,
+            );
+
+            (relation_name, document)
+        })
+        .collect();
+
+    Ok(operations)
+}
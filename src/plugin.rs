@@ -0,0 +1,177 @@
+use crate::{
+    error::{GeneratorError, Result},
+    types::{ColumnMeta, ForeignKeyMeta, ManyToManyMeta, TableMeta},
+};
+use proc_macro2::TokenStream;
+use std::collections::BTreeMap;
+
+// hooks into the generation of the GraphQL layer, registered with `Generator::plugin`
+// every method has a default, a plugin only implements what it changes
+pub trait GeneratorPlugin {
+    // item hooks run before anything is generated, in registration order
+    // returning `false` leaves the item out, names can be changed through the meta
+    // (`entity_name`, `field_name`, `relation_name`, ...), the database names must stay as they are
+    fn table(&self, _table: &mut TableMeta) -> bool {
+        true
+    }
+
+    fn column(&self, _table: &TableMeta, _column: &mut ColumnMeta) -> bool {
+        true
+    }
+
+    // called for every table exposing the key, `relation_name` names the field of the source table
+    // and `reverse_relation_name` the one of the destination, leaving it out removes the fields of `table`
+    fn relation(&self, _table: &TableMeta, _fk: &mut ForeignKeyMeta) -> bool {
+        true
+    }
+
+    fn many_to_many(&self, _table: &TableMeta, _relation: &mut ManyToManyMeta) -> bool {
+        true
+    }
+
+    // derives added to the `Filter` input of an entity, e.g. `quote!(Clone)`
+    fn filter_derives(&self, _table: &TableMeta) -> Vec<TokenStream> {
+        vec![]
+    }
+
+    // attributes of the column getter, e.g. `quote!(#[graphql(deprecation = "use fullName")])`
+    fn column_attributes(&self, _table: &TableMeta, _column: &ColumnMeta) -> Vec<TokenStream> {
+        vec![]
+    }
+
+    // methods added to the `#[async_graphql::Object]` impl of the entity `Model`
+    fn entity_resolvers(&self, _table: &TableMeta) -> Vec<TokenStream> {
+        vec![]
+    }
+
+    // items added to the entity module below `graphql::entities`
+    fn entity_items(&self, _table: &TableMeta) -> Vec<TokenStream> {
+        vec![]
+    }
+
+    // methods added to the `#[async_graphql::Object]` impl of `QueryRoot`
+    fn root_resolvers(&self, _tables_meta: &[TableMeta]) -> Vec<TokenStream> {
+        vec![]
+    }
+
+    // modules next to `entities` below `graphql`, keyed by module name
+    fn modules(&self, _tables_meta: &[TableMeta]) -> BTreeMap<String, TokenStream> {
+        BTreeMap::new()
+    }
+}
+
+pub type Plugins = [Box<dyn GeneratorPlugin>];
+
+// runs the item hooks, relations to left out tables are dropped
+// columns left out are only hidden from the API, relations using them keep working
+pub fn apply_plugins(plugins: &Plugins, tables_meta: Vec<TableMeta>) -> Vec<TableMeta> {
+    if plugins.is_empty() {
+        return tables_meta;
+    }
+
+    let mut entity_names: BTreeMap<String, Option<String>> = BTreeMap::new();
+
+    let tables_meta: Vec<TableMeta> = tables_meta
+        .into_iter()
+        .filter_map(|mut table: TableMeta| {
+            let entity_name = table.entity_name.clone();
+
+            let included = plugins.iter().all(|plugin| plugin.table(&mut table));
+
+            // filter and result names are built from the entity name
+            if included && table.entity_name.ne(&entity_name) {
+                table.rename(table.entity_name.clone());
+            }
+
+            entity_names.insert(entity_name, included.then(|| table.entity_name.clone()));
+
+            included.then_some(table)
+        })
+        .collect();
+
+    let tables_meta: Vec<TableMeta> = tables_meta
+        .into_iter()
+        .map(|mut table: TableMeta| {
+            let columns: Vec<ColumnMeta> = table
+                .columns
+                .iter()
+                .cloned()
+                .filter_map(|mut column: ColumnMeta| {
+                    plugins
+                        .iter()
+                        .all(|plugin| plugin.column(&table, &mut column))
+                        .then_some(column)
+                })
+                .collect();
+
+            table.columns = columns;
+
+            table
+        })
+        .collect();
+
+    // `None` when a table of the key was left out
+    let rename_fk = |mut fk: ForeignKeyMeta| -> Option<ForeignKeyMeta> {
+        fk.source_table_name = entity_names.get(&fk.source_table_name)?.clone()?;
+        fk.destination_table_name = entity_names.get(&fk.destination_table_name)?.clone()?;
+
+        Some(fk)
+    };
+
+    tables_meta
+        .iter()
+        .map(|table: &TableMeta| {
+            let mut table = table.clone();
+
+            table.foreign_keys = table
+                .foreign_keys
+                .iter()
+                .cloned()
+                .filter_map(|fk: ForeignKeyMeta| {
+                    let mut fk = rename_fk(fk)?;
+
+                    plugins
+                        .iter()
+                        .all(|plugin| plugin.relation(&table, &mut fk))
+                        .then_some(fk)
+                })
+                .collect();
+
+            table.many_to_many = table
+                .many_to_many
+                .iter()
+                .cloned()
+                .filter_map(|mut relation: ManyToManyMeta| {
+                    relation.source_foreign_key = rename_fk(relation.source_foreign_key)?;
+                    relation.destination_foreign_key = rename_fk(relation.destination_foreign_key)?;
+
+                    plugins
+                        .iter()
+                        .all(|plugin| plugin.many_to_many(&table, &mut relation))
+                        .then_some(relation)
+                })
+                .collect();
+
+            table
+        })
+        .collect()
+}
+
+// generated modules and the modules of every plugin, plugins cannot replace generated code
+pub fn merge_plugin_modules(
+    plugins: &Plugins,
+    tables_meta: &[TableMeta],
+    mut modules: BTreeMap<String, TokenStream>,
+) -> Result<BTreeMap<String, TokenStream>> {
+    for plugin in plugins.iter() {
+        for (name, module) in plugin.modules(tables_meta).into_iter() {
+            if modules.contains_key(&name) || name.eq("entities") {
+                return Err(GeneratorError::Plugin(format!("module `{}` is already defined", name)));
+            }
+
+            modules.insert(name, module);
+        }
+    }
+
+    Ok(modules)
+}
@@ -0,0 +1,488 @@
+use crate::{
+    client_generator::generate_client,
+    config::{GeneratorConfig, OutputMode, WebFramework},
+    error::{GeneratorError, Result},
+    entities_generator::generate_entities_files,
+    formatter::{format_source, format_tokens, syntax_tokens},
+    graphql::{
+        extensions::EXTENSION_PLACEHOLDER,
+        generate_graphql,
+        introspection::INTROSPECTION_QUERY,
+    },
+    operations_generator::generate_operations,
+    plugin::Plugins,
+    settings_generator::generate_settings,
+    toml_generator::{generate_client_toml, generate_toml},
+    typescript_generator::generate_typescript,
+    types::TableMeta,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use sea_schema::sea_query::table::TableCreateStatement;
+use std::{collections::BTreeMap, fs, path::Path, process::Command};
+
+// directories that only contain generated files, anything else found there is stale
+const GENERATED_DIRS: [&str; 4] = ["src/orm", "src/graphql", "client/src", "operations"];
+
+const SCHEMA_CHECK_PACKAGE: &str = "schema_check";
+
+// the crate root, also everything an existing server needs to mount the API
+pub fn generate_lib(web_framework: WebFramework) -> TokenStream {
+    let router = generate_router(web_framework);
+
+    quote! {
+        pub mod orm;
+        pub mod graphql;
+        pub mod extensions;
+
+        pub use graphql::Query;
+        pub use graphql::QueryRoot;
+        pub use graphql::OrmDataLoader;
+
+        pub type QuerySchema = async_graphql::Schema<Query, async_graphql::EmptyMutation, async_graphql::EmptySubscription>;
+
+        // the schema resolving queries with `db`, ready to be served
+        pub fn build_schema(db: sea_orm::DatabaseConnection) -> QuerySchema {
+            let orm_data_loader: async_graphql::dataloader::DataLoader<OrmDataLoader> = async_graphql::dataloader::DataLoader::new(
+                OrmDataLoader {
+                    db: db.clone()
+                },
+                tokio::spawn
+            );
+
+            async_graphql::Schema::build(Query::default(), async_graphql::EmptyMutation, async_graphql::EmptySubscription)
+                .data(db)
+                .data(orm_data_loader)
+                .finish()
+        }
+
+        #router
+    }
+}
+
+// serves GraphQL on `POST /` and the playground on `GET <playground>` relative to where it is mounted,
+// `None` leaves the playground out, `endpoint` is the path it sends its queries to, e.g. "/graphql"
+fn generate_router(web_framework: WebFramework) -> TokenStream {
+    match web_framework {
+        WebFramework::Poem => quote! {
+            pub fn router(schema: QuerySchema, endpoint: &str, playground: Option<&str>) -> poem::Route {
+                let graphql = async_graphql_poem::GraphQL::new(schema);
+
+                let playground_source = async_graphql::http::playground_source(async_graphql::http::GraphQLPlaygroundConfig::new(endpoint));
+                let playground_endpoint = poem::endpoint::make_sync(move |_| poem::web::Html(playground_source.clone()));
+
+                // the methods of a path are registered together
+                match playground {
+                    None => poem::Route::new().at("/", poem::post(graphql)),
+                    Some("/") => poem::Route::new().at("/", poem::get(playground_endpoint).post(graphql)),
+                    Some(path) => poem::Route::new()
+                        .at("/", poem::post(graphql))
+                        .at(path, poem::get(playground_endpoint)),
+                }
+            }
+        },
+        WebFramework::Axum => quote! {
+            pub fn router(schema: QuerySchema, endpoint: &str, playground: Option<&str>) -> axum::Router {
+                let playground_source = async_graphql::http::playground_source(async_graphql::http::GraphQLPlaygroundConfig::new(endpoint));
+                let playground_handler = move || async move { axum::response::Html(playground_source) };
+
+                // the methods of a path are registered together
+                let router = match playground {
+                    None => axum::Router::new().route("/", axum::routing::post(graphql_handler)),
+                    Some("/") => axum::Router::new().route(
+                        "/",
+                        axum::routing::get(playground_handler).post(graphql_handler),
+                    ),
+                    Some(path) => axum::Router::new()
+                        .route("/", axum::routing::post(graphql_handler))
+                        .route(path, axum::routing::get(playground_handler)),
+                };
+
+                router.layer(axum::extract::Extension(schema))
+            }
+
+            async fn graphql_handler(
+                schema: axum::extract::Extension<QuerySchema>,
+                request: async_graphql_axum::GraphQLRequest,
+            ) -> async_graphql_axum::GraphQLResponse {
+                schema.execute(request.into_inner()).await.into()
+            }
+        },
+        // `""` matches the path of a scope, `"/"` the root of an application
+        WebFramework::ActixWeb => quote! {
+            pub fn configure(
+                schema: QuerySchema,
+                endpoint: &str,
+                playground: Option<&str>,
+            ) -> impl FnOnce(&mut actix_web::web::ServiceConfig) {
+                let playground_source = async_graphql::http::playground_source(async_graphql::http::GraphQLPlaygroundConfig::new(endpoint));
+
+                let playground: Option<Vec<String>> = playground.map(|path| match path {
+                    "/" => vec!["".into(), "/".into()],
+                    path => vec![path.into()],
+                });
+
+                move |config: &mut actix_web::web::ServiceConfig| {
+                    config
+                        .app_data(actix_web::web::Data::new(schema))
+                        .service(
+                            actix_web::web::resource(["", "/"])
+                                .guard(actix_web::guard::Post())
+                                .to(graphql_handler),
+                        );
+
+                    if let Some(paths) = playground {
+                        config.service(
+                            actix_web::web::resource(paths)
+                                .guard(actix_web::guard::Get())
+                                .to(move || {
+                                    let playground_source = playground_source.clone();
+
+                                    async move {
+                                        actix_web::HttpResponse::Ok()
+                                            .content_type("text/html; charset=utf-8")
+                                            .body(playground_source)
+                                    }
+                                }),
+                        );
+                    }
+                }
+            }
+
+            async fn graphql_handler(
+                schema: actix_web::web::Data<QuerySchema>,
+                request: async_graphql_actix_web::GraphQLRequest,
+            ) -> async_graphql_actix_web::GraphQLResponse {
+                schema.execute(request.into_inner()).await.into()
+            }
+        },
+    }
+}
+
+pub fn generate_main(project_name: &str, web_framework: WebFramework) -> TokenStream {
+    // cargo turns dashes of the package name into underscores
+    let crate_name = format_ident!("{}", project_name.replace('-', "_"));
+    let introspection_query = INTROSPECTION_QUERY;
+    let extension_placeholder = format!("{}: Boolean!", EXTENSION_PLACEHOLDER);
+
+    let (main_attribute, serve) = generate_server(web_framework);
+
+    quote! {
+        use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+        use sea_orm::{ConnectOptions, Database};
+
+        use #crate_name::*;
+
+        mod settings;
+
+        use settings::Settings;
+
+        const INTROSPECTION_QUERY: &str = #introspection_query;
+
+        #main_attribute
+        async fn main() {
+            // writes the schema for client tooling without connecting to the database
+            if std::env::args().any(|argument| argument.eq("--print-schema")) {
+                let schema = Schema::build(Query::default(), EmptyMutation, EmptySubscription).finish();
+
+                let sdl = schema.sdl();
+                let sdl: Vec<&str> = sdl
+                    .lines()
+                    .filter(|line| !line.trim().eq(#extension_placeholder))
+                    .collect();
+
+                std::fs::write("schema.graphql", format!("{}\n", sdl.join("\n"))).unwrap();
+
+                let introspection = schema.execute(INTROSPECTION_QUERY).await;
+
+                std::fs::write("schema.json", serde_json::to_string_pretty(&introspection).unwrap()).unwrap();
+
+                println!("Wrote schema.graphql and schema.json");
+
+                return;
+            }
+
+            let settings = match Settings::load() {
+                Ok(settings) => settings,
+                Err(errors) => {
+                    eprintln!("Invalid settings:");
+
+                    for error in errors.iter() {
+                        eprintln!("  {}", error);
+                    }
+
+                    std::process::exit(1);
+                }
+            };
+
+            tracing_subscriber::fmt()
+                .with_max_level(settings.log_level)
+                .with_test_writer()
+                .init();
+
+            let mut options = ConnectOptions::new(settings.database_url.clone());
+            options.max_connections(settings.database_pool_size);
+
+            let database = match Database::connect(options).await {
+                Ok(database) => database,
+                Err(error) => {
+                    eprintln!("Cannot connect to {}: {}", settings.database_url, error);
+
+                    std::process::exit(1);
+                }
+            };
+
+            let schema = build_schema(database);
+
+            if let Some(path) = settings.playground() {
+                println!("Playground: http://{}{}", settings.bind_address, path);
+            }
+
+            #serve
+        }
+
+    }
+}
+
+// attribute of `main` and the statements serving `schema` through the router of `lib.rs`
+fn generate_server(web_framework: WebFramework) -> (TokenStream, TokenStream) {
+    match web_framework {
+        WebFramework::Poem => (
+            quote! {
+                #[tokio::main]
+            },
+            quote! {
+                poem::Server::new(poem::listener::TcpListener::bind(settings.bind_address))
+                    .run(router(schema, "/", settings.playground()))
+                    .await
+                    .unwrap();
+            },
+        ),
+        WebFramework::Axum => (
+            quote! {
+                #[tokio::main]
+            },
+            quote! {
+                axum::Server::bind(&settings.bind_address)
+                    .serve(router(schema, "/", settings.playground()).into_make_service())
+                    .await
+                    .unwrap();
+            },
+        ),
+        WebFramework::ActixWeb => (
+            quote! {
+                #[actix_web::main]
+            },
+            quote! {
+                let playground: Option<String> = settings.playground().map(String::from);
+
+                actix_web::HttpServer::new(move || {
+                    actix_web::App::new().configure(configure(schema.clone(), "/", playground.as_deref()))
+                })
+                .bind(settings.bind_address)
+                .unwrap()
+                .run()
+                .await
+                .unwrap();
+            },
+        ),
+    }
+}
+
+// every file the generator owns, keyed by its path relative to the project directory
+// the user owned extension files are not part of it, rust files are formatted
+pub fn generate_project(
+    project_name: &str,
+    tables_meta: &[TableMeta],
+    table_create_stmts: Vec<TableCreateStatement>,
+    config: &GeneratorConfig,
+    plugins: &Plugins,
+) -> Result<BTreeMap<String, String>> {
+    let mut files: BTreeMap<String, String> = BTreeMap::new();
+
+    files.insert(
+        "Cargo.toml".into(),
+        generate_toml(project_name, config.web_framework, config.output)?,
+    );
+
+    for (name, content) in generate_entities_files(table_create_stmts)?.into_iter() {
+        let name = format!("src/orm/{}", name);
+        let content = format_source(&name, &content)?;
+
+        files.insert(name, content);
+    }
+
+    for (name, tokens) in generate_graphql(tables_meta, plugins)?.iter() {
+        let name = format!("src/graphql/{}", name);
+        let content = format_tokens(&name, tokens)?;
+
+        files.insert(name, content);
+    }
+
+    files.insert("src/lib.rs".into(), format_tokens("src/lib.rs", &generate_lib(config.web_framework))?);
+
+    files.insert("types.ts".into(), generate_typescript(tables_meta));
+
+    for (name, content) in generate_operations(tables_meta)?.into_iter() {
+        files.insert(format!("operations/{}", name), content);
+    }
+
+    if config.output.eq(&OutputMode::Binary) {
+        files.insert(
+            "src/main.rs".into(),
+            format_tokens("src/main.rs", &generate_main(project_name, config.web_framework))?,
+        );
+        files.insert("src/settings.rs".into(), format_tokens("src/settings.rs", &generate_settings())?);
+    }
+
+    // companion crate other services use to query the generated API
+    files.insert(
+        "client/Cargo.toml".into(),
+        generate_client_toml(&format!("{}_client", project_name))?,
+    );
+
+    for (name, tokens) in generate_client(tables_meta).iter() {
+        let name = format!("client/{}", name);
+        let content = format_tokens(&name, tokens)?;
+
+        files.insert(name, content);
+    }
+
+    Ok(files)
+}
+
+pub fn write_project(project_dir: &Path, files: &BTreeMap<String, String>) -> Result<()> {
+    for (name, content) in files.iter() {
+        let file_path = project_dir.join(name);
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(file_path, content)?;
+    }
+
+    Ok(())
+}
+
+// builds a copy of the project below its target directory and runs `--print-schema` there
+pub fn generate_sdl(project_dir: &Path, files: &BTreeMap<String, String>, web_framework: WebFramework) -> Result<String> {
+    let target_dir = project_dir.canonicalize()?.join("target");
+    let check_dir = target_dir.join("schema-check");
+
+    let _ = fs::remove_dir_all(&check_dir);
+
+    // a package of its own keeps the binary of the project in the shared target directory intact
+    // libraries get the `main.rs` of a binary to print their schema
+    let mut files = files.clone();
+    files.insert(
+        "Cargo.toml".into(),
+        generate_toml(SCHEMA_CHECK_PACKAGE, web_framework, OutputMode::Binary)?,
+    );
+    files.insert("src/main.rs".into(), format_tokens("src/main.rs", &generate_main(SCHEMA_CHECK_PACKAGE, web_framework))?);
+    files.insert("src/settings.rs".into(), format_tokens("src/settings.rs", &generate_settings())?);
+
+    write_project(&check_dir, &files)?;
+
+    // user owned files and locked versions are taken over as they are
+    copy_files(&project_dir.join("src/extensions"), &check_dir.join("src/extensions"))?;
+
+    if project_dir.join("Cargo.lock").exists() {
+        fs::copy(project_dir.join("Cargo.lock"), check_dir.join("Cargo.lock"))?;
+    }
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "--print-schema"])
+        .current_dir(&check_dir)
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(GeneratorError::Command(String::from_utf8_lossy(&output.stderr).into()));
+    }
+
+    Ok(fs::read_to_string(check_dir.join("schema.graphql"))?)
+}
+
+fn copy_files(source_dir: &Path, destination_dir: &Path) -> Result<()> {
+    fs::create_dir_all(destination_dir)?;
+
+    for entry in fs::read_dir(source_dir)? {
+        let entry = entry?;
+        let destination = destination_dir.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_files(&entry.path(), &destination)?;
+        } else if !destination.exists() {
+            fs::copy(entry.path(), destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+// returns one line per file that is out of date, missing or stale
+pub fn check_project(project_dir: &Path, files: &BTreeMap<String, String>) -> Result<Vec<String>> {
+    let mut differences: Vec<String> = Vec::new();
+
+    for (name, content) in files.iter() {
+        let existing = match fs::read_to_string(project_dir.join(name)) {
+            Ok(existing) => existing,
+            Err(_) => {
+                differences.push(format!("missing: {}", name));
+                continue;
+            }
+        };
+
+        if !is_same_content(name, &existing, content) {
+            differences.push(format!("out of date: {}", name));
+        }
+    }
+
+    for dir in GENERATED_DIRS.iter() {
+        for name in list_files(project_dir, dir)?.into_iter() {
+            if !files.contains_key(&name) {
+                differences.push(format!("stale: {}", name));
+            }
+        }
+    }
+
+    Ok(differences)
+}
+
+// rust files are compared by syntax tree, a project formatted with `cargo fmt` is not out of date
+fn is_same_content(name: &str, existing: &str, content: &str) -> bool {
+    if !name.ends_with(".rs") {
+        return existing.eq(content);
+    }
+
+    // code that does not parse is compared as is and reported as out of date
+    match (syntax_tokens(name, existing), syntax_tokens(name, content)) {
+        (Ok(existing_tokens), Ok(tokens)) => existing_tokens.eq(&tokens),
+        _ => existing.eq(content),
+    }
+}
+
+fn list_files(project_dir: &Path, dir: &str) -> Result<Vec<String>> {
+    let entries = match fs::read_dir(project_dir.join(dir)) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut names: Vec<String> = Vec::new();
+
+    for entry in entries {
+        let entry = entry?;
+        let name = format!("{}/{}", dir, entry.file_name().to_string_lossy());
+
+        if entry.file_type()?.is_dir() {
+            names.append(&mut list_files(project_dir, &name)?);
+        } else {
+            names.push(name);
+        }
+    }
+
+    names.sort();
+
+    Ok(names)
+}
@@ -0,0 +1,68 @@
+// how column types are exposed, shared by the generated project, the dynamic schema and the generated clients
+
+// GraphQL scalar name and the JSON type async-graphql serializes it as
+pub const SCALARS: [(&str, &str); 11] = [
+    ("ID", "string"),
+    ("String", "string"),
+    ("Boolean", "boolean"),
+    ("Int", "number"),
+    ("Float", "number"),
+    ("Decimal", "string"),
+    ("NaiveDate", "string"),
+    ("NaiveTime", "string"),
+    ("NaiveDateTime", "string"),
+    ("DateTime", "string"),
+    ("UUID", "string"),
+];
+
+// concrete `TypeFilter` names, see `graphql::type_filter`
+pub const FILTERS: [(&str, &str); 14] = [
+    ("StringFilter", "String"),
+    ("TinyIntegerFilter", "Int"),
+    ("SmallIntegerFilter", "Int"),
+    ("IntegerFilter", "Int"),
+    ("BigIntegerFilter", "Int"),
+    ("TinyUnsignedFilter", "Int"),
+    ("SmallUnsignedFilter", "Int"),
+    ("UnsignedFilter", "Int"),
+    ("BigUnsignedFilter", "Int"),
+    ("FloatFilter", "Float"),
+    ("DoubleFilter", "Float"),
+    ("DateTimeFilter", "NaiveDateTime"),
+    ("DecimalFilter", "Decimal"),
+    ("BooleanFilter", "Boolean"),
+];
+
+// rust type of a column (without `Option`) -> GraphQL scalar and filter name
+pub fn get_scalar(column_type: &str) -> (&'static str, Option<&'static str>) {
+    match column_type {
+        "String" => ("String", Some("StringFilter")),
+        "i8" => ("Int", Some("TinyIntegerFilter")),
+        "i16" => ("Int", Some("SmallIntegerFilter")),
+        "i32" => ("Int", Some("IntegerFilter")),
+        "i64" => ("Int", Some("BigIntegerFilter")),
+        "u8" => ("Int", Some("TinyUnsignedFilter")),
+        "u16" => ("Int", Some("SmallUnsignedFilter")),
+        "u32" => ("Int", Some("UnsignedFilter")),
+        "u64" => ("Int", Some("BigUnsignedFilter")),
+        "f32" => ("Float", Some("FloatFilter")),
+        "f64" => ("Float", Some("DoubleFilter")),
+        "bool" => ("Boolean", Some("BooleanFilter")),
+        "Decimal" => ("Decimal", Some("DecimalFilter")),
+        "DateTime" => ("NaiveDateTime", Some("DateTimeFilter")),
+        "Date" => ("NaiveDate", None),
+        "Time" => ("NaiveTime", None),
+        "DateTimeWithTimeZone" => ("DateTime", None),
+        "Uuid" => ("UUID", None),
+        _ => ("String", None),
+    }
+}
+
+// JSON type of a GraphQL scalar, "string" for unknown ones
+pub fn get_json_type(scalar: &str) -> &'static str {
+    SCALARS
+        .iter()
+        .find(|(name, _)| name.eq(&scalar))
+        .map(|(_, json_type)| *json_type)
+        .unwrap_or("string")
+}
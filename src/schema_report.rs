@@ -0,0 +1,374 @@
+use crate::{
+    error::{GeneratorError, Result},
+    types::schema_snapshot::{ColumnSnapshot, RelationSnapshot, SchemaSnapshot, TableSnapshot},
+};
+use heck::ToSnakeCase;
+use serde_derive::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaChangeKind {
+    TableAdded,
+    TableRemoved,
+    TableRenamed,
+    ColumnAdded,
+    ColumnRemoved,
+    ColumnRenamed,
+    ColumnTypeChanged,
+    ColumnNullabilityChanged,
+    PrimaryKeyChanged,
+    RelationAdded,
+    RelationRemoved,
+    RelationChanged,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SchemaChange {
+    pub kind: SchemaChangeKind,
+    pub table: String, // as in the database
+    pub target: Option<String>, // column or relation field
+    pub previous: Option<String>,
+    pub current: Option<String>,
+    pub breaking: bool, // existing GraphQL queries may fail
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SchemaReport {
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaReport {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(|change: &SchemaChange| change.breaking)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|error| GeneratorError::Serialization {
+            file: "schema report".into(),
+            message: error.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let marker = if self.breaking { "BREAKING" } else { "        " };
+
+        // the JSON name without underscores, e.g. "column added"
+        let kind = format!("{:?}", self.kind).to_snake_case().replace('_', " ");
+
+        write!(f, "{} {}: {}", marker, kind, self.table)?;
+
+        if let Some(target) = &self.target {
+            write!(f, ".{}", target)?;
+        }
+
+        match (&self.previous, &self.current) {
+            (Some(previous), Some(current)) => write!(f, " ({} -> {})", previous, current),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn compare_schemas(previous: &SchemaSnapshot, current: &SchemaSnapshot) -> SchemaReport {
+    let mut changes: Vec<SchemaChange> = Vec::new();
+
+    for table in previous.tables.iter() {
+        if current.get_table(&table.table_name).is_none() {
+            changes.push(change(SchemaChangeKind::TableRemoved, &table.table_name, None, None, None));
+        }
+    }
+
+    for table in current.tables.iter() {
+        match previous.get_table(&table.table_name) {
+            Some(previous_table) => compare_tables(&mut changes, previous_table, table),
+            None => changes.push(change(SchemaChangeKind::TableAdded, &table.table_name, None, None, None)),
+        }
+    }
+
+    SchemaReport { changes }
+}
+
+fn compare_tables(changes: &mut Vec<SchemaChange>, previous: &TableSnapshot, current: &TableSnapshot) {
+    let table_name: &String = &current.table_name;
+
+    if previous.entity_name.ne(&current.entity_name) {
+        changes.push(change(
+            SchemaChangeKind::TableRenamed,
+            table_name,
+            None,
+            Some(previous.entity_name.clone()),
+            Some(current.entity_name.clone()),
+        ));
+    }
+
+    let primary_key = |table: &TableSnapshot| -> String {
+        table
+            .columns
+            .iter()
+            .filter(|column: &&ColumnSnapshot| column.is_primary_key)
+            .map(|column: &ColumnSnapshot| column.column_name.clone())
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    if primary_key(previous).ne(&primary_key(current)) {
+        changes.push(change(
+            SchemaChangeKind::PrimaryKeyChanged,
+            table_name,
+            None,
+            Some(primary_key(previous)),
+            Some(primary_key(current)),
+        ));
+    }
+
+    for column in previous.columns.iter() {
+        if current.get_column(&column.column_name).is_none() {
+            changes.push(change(SchemaChangeKind::ColumnRemoved, table_name, Some(&column.column_name), None, None));
+        }
+    }
+
+    for column in current.columns.iter() {
+        let previous_column: &ColumnSnapshot = match previous.get_column(&column.column_name) {
+            Some(previous_column) => previous_column,
+            None => {
+                changes.push(change(SchemaChangeKind::ColumnAdded, table_name, Some(&column.column_name), None, None));
+                continue;
+            }
+        };
+
+        if previous_column.field_name.ne(&column.field_name) {
+            changes.push(change(
+                SchemaChangeKind::ColumnRenamed,
+                table_name,
+                Some(&column.column_name),
+                Some(previous_column.field_name.clone()),
+                Some(column.field_name.clone()),
+            ));
+        }
+
+        if previous_column.column_type.ne(&column.column_type) {
+            changes.push(change(
+                SchemaChangeKind::ColumnTypeChanged,
+                table_name,
+                Some(&column.column_name),
+                Some(previous_column.column_type.clone()),
+                Some(column.column_type.clone()),
+            ));
+        }
+
+        if previous_column.not_null != column.not_null {
+            let nullability = |not_null: bool| -> String {
+                if not_null { "not null".into() } else { "nullable".into() }
+            };
+
+            let mut nullability_change = change(
+                SchemaChangeKind::ColumnNullabilityChanged,
+                table_name,
+                Some(&column.column_name),
+                Some(nullability(previous_column.not_null)),
+                Some(nullability(column.not_null)),
+            );
+
+            // clients always handle a value that can no longer be null
+            nullability_change.breaking = !column.not_null;
+
+            changes.push(nullability_change);
+        }
+    }
+
+    for relation in previous.relations.iter() {
+        if current.get_relation(&relation.field_name).is_none() {
+            changes.push(change(SchemaChangeKind::RelationRemoved, table_name, Some(&relation.field_name), None, None));
+        }
+    }
+
+    for relation in current.relations.iter() {
+        let previous_relation: &RelationSnapshot = match previous.get_relation(&relation.field_name) {
+            Some(previous_relation) => previous_relation,
+            None => {
+                changes.push(change(SchemaChangeKind::RelationAdded, table_name, Some(&relation.field_name), None, None));
+                continue;
+            }
+        };
+
+        let describe = |relation: &RelationSnapshot| -> String {
+            if relation.is_list {
+                format!("[{}]", relation.destination_table)
+            } else {
+                relation.destination_table.clone()
+            }
+        };
+
+        if describe(previous_relation).ne(&describe(relation)) {
+            changes.push(change(
+                SchemaChangeKind::RelationChanged,
+                table_name,
+                Some(&relation.field_name),
+                Some(describe(previous_relation)),
+                Some(describe(relation)),
+            ));
+        }
+    }
+}
+
+fn change(
+    kind: SchemaChangeKind,
+    table: &str,
+    target: Option<&String>,
+    previous: Option<String>,
+    current: Option<String>,
+) -> SchemaChange {
+    // additions only extend the API, everything else can invalidate existing queries
+    let breaking = !matches!(
+        kind,
+        SchemaChangeKind::TableAdded | SchemaChangeKind::ColumnAdded | SchemaChangeKind::RelationAdded
+    );
+
+    SchemaChange {
+        kind,
+        table: table.into(),
+        target: target.cloned(),
+        previous,
+        current,
+        breaking,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_schemas, SchemaChangeKind};
+    use crate::types::schema_snapshot::{ColumnSnapshot, RelationSnapshot, SchemaSnapshot, TableSnapshot};
+
+    fn column(column_name: &str, not_null: bool, is_primary_key: bool) -> ColumnSnapshot {
+        ColumnSnapshot {
+            column_name: column_name.into(),
+            field_name: column_name.into(),
+            column_type: "i32".into(),
+            not_null,
+            is_primary_key,
+        }
+    }
+
+    fn albums() -> TableSnapshot {
+        TableSnapshot {
+            table_name: "albums".into(),
+            entity_name: "Albums".into(),
+            columns: vec![column("album_id", true, true), column("artist_id", true, false)],
+            relations: vec![RelationSnapshot {
+                field_name: "artist".into(),
+                destination_table: "Artists".into(),
+                is_list: false,
+            }],
+        }
+    }
+
+    fn snapshot(tables: Vec<TableSnapshot>) -> SchemaSnapshot {
+        SchemaSnapshot { tables, ..Default::default() }
+    }
+
+    // kinds and whether they are breaking, in the order they are reported
+    fn classify(current: TableSnapshot) -> Vec<(SchemaChangeKind, bool)> {
+        compare_schemas(&snapshot(vec![albums()]), &snapshot(vec![current]))
+            .changes
+            .into_iter()
+            .map(|change| (change.kind, change.breaking))
+            .collect()
+    }
+
+    #[test]
+    fn same_schema_has_no_changes() {
+        assert!(compare_schemas(&snapshot(vec![albums()]), &snapshot(vec![albums()])).is_empty());
+    }
+
+    #[test]
+    fn tables_added_and_removed() {
+        let mut artists = albums();
+        artists.table_name = "artists".into();
+
+        let report = compare_schemas(&snapshot(vec![albums()]), &snapshot(vec![artists]));
+        let changes: Vec<(SchemaChangeKind, &str, bool)> = report
+            .changes
+            .iter()
+            .map(|change| (change.kind, change.table.as_str(), change.breaking))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![(SchemaChangeKind::TableRemoved, "albums", true), (SchemaChangeKind::TableAdded, "artists", false)]
+        );
+        assert!(report.has_breaking_changes());
+    }
+
+    #[test]
+    fn column_additions_are_not_breaking() {
+        let mut current = albums();
+        current.columns.push(column("title", false, false));
+
+        assert_eq!(classify(current), vec![(SchemaChangeKind::ColumnAdded, false)]);
+
+        let mut current = albums();
+        current.columns.pop();
+
+        assert_eq!(classify(current), vec![(SchemaChangeKind::ColumnRemoved, true)]);
+    }
+
+    #[test]
+    fn only_columns_becoming_nullable_are_breaking() {
+        let mut nullable = albums();
+        nullable.columns[1].not_null = false;
+
+        assert_eq!(classify(nullable.clone()), vec![(SchemaChangeKind::ColumnNullabilityChanged, true)]);
+
+        let report = compare_schemas(&snapshot(vec![nullable]), &snapshot(vec![albums()]));
+
+        assert_eq!(report.changes[0].kind, SchemaChangeKind::ColumnNullabilityChanged);
+        assert!(!report.has_breaking_changes());
+    }
+
+    #[test]
+    fn renames_keys_and_relations_are_breaking() {
+        let mut renamed = albums();
+        renamed.entity_name = "Album".into();
+        renamed.columns[1].field_name = "artist".into();
+
+        assert_eq!(
+            classify(renamed),
+            vec![(SchemaChangeKind::TableRenamed, true), (SchemaChangeKind::ColumnRenamed, true)]
+        );
+
+        let mut primary_key = albums();
+        primary_key.columns[1].is_primary_key = true;
+
+        assert_eq!(classify(primary_key), vec![(SchemaChangeKind::PrimaryKeyChanged, true)]);
+
+        let mut relation = albums();
+        relation.relations[0].is_list = true;
+
+        assert_eq!(classify(relation), vec![(SchemaChangeKind::RelationChanged, true)]);
+    }
+
+    #[test]
+    fn report_lines() {
+        let mut current = albums();
+        current.columns[0].column_type = "i64".into();
+
+        let report = compare_schemas(&snapshot(vec![albums()]), &snapshot(vec![current]));
+
+        assert_eq!(report.to_string(), "BREAKING column type changed: albums.album_id (i32 -> i64)\n");
+    }
+}
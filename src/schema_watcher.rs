@@ -0,0 +1,12 @@
+use crate::error::{GeneratorError, Result};
+use sqlx::{Pool, Sqlite};
+
+// sqlite bumps `schema_version` on every schema change, data writes leave it untouched
+pub async fn get_schema_version(connection: &Pool<Sqlite>) -> Result<i64> {
+    let (version,): (i64,) = sqlx::query_as("PRAGMA schema_version")
+        .fetch_one(connection)
+        .await
+        .map_err(GeneratorError::Connection)?;
+
+    Ok(version)
+}
@@ -0,0 +1,319 @@
+use async_graphql::parser::{
+    parse_schema,
+    types::{BaseType, FieldDefinition, InputValueDefinition, Type, TypeDefinition, TypeKind, TypeSystemDefinition},
+    Positioned,
+};
+use crate::error::{GeneratorError, Result};
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SdlChange {
+    pub coordinate: String, // `Type`, `Type.field` or `Type.field(argument:)`
+    pub description: String,
+    pub breaking: bool, // existing client operations may fail
+}
+
+impl std::fmt::Display for SdlChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let marker = if self.breaking { "BREAKING" } else { "        " };
+
+        write!(f, "{} {}: {}", marker, self.coordinate, self.description)
+    }
+}
+
+pub fn compare_sdl(previous: &str, current: &str) -> Result<Vec<SdlChange>> {
+    let previous_types = parse_types(previous)?;
+    let current_types = parse_types(current)?;
+
+    let mut changes: Vec<SdlChange> = Vec::new();
+
+    for name in previous_types.keys() {
+        if !current_types.contains_key(name) {
+            changes.push(change(name, "type removed".into(), true));
+        }
+    }
+
+    for (name, current_type) in current_types.iter() {
+        match previous_types.get(name) {
+            Some(previous_type) => compare_types(&mut changes, name, previous_type, current_type),
+            None => changes.push(change(name, "type added".into(), false)),
+        }
+    }
+
+    Ok(changes)
+}
+
+fn parse_types(sdl: &str) -> Result<BTreeMap<String, TypeKind>> {
+    let types = parse_schema(sdl)
+        .map_err(|error| GeneratorError::InvalidSdl(error.to_string()))?
+        .definitions
+        .into_iter()
+        .filter_map(|definition: TypeSystemDefinition| match definition {
+            TypeSystemDefinition::Type(definition) => {
+                let TypeDefinition { name, kind, .. } = definition.node;
+
+                Some((name.node.to_string(), kind))
+            }
+            _ => None,
+        })
+        .collect();
+
+    Ok(types)
+}
+
+fn compare_types(changes: &mut Vec<SdlChange>, type_name: &str, previous: &TypeKind, current: &TypeKind) {
+    match (previous, current) {
+        (TypeKind::Scalar, TypeKind::Scalar) => {}
+        (TypeKind::Object(previous), TypeKind::Object(current)) => {
+            compare_fields(changes, type_name, &previous.fields, &current.fields)
+        }
+        (TypeKind::Interface(previous), TypeKind::Interface(current)) => {
+            compare_fields(changes, type_name, &previous.fields, &current.fields)
+        }
+        (TypeKind::InputObject(previous), TypeKind::InputObject(current)) => {
+            compare_input_values(changes, type_name, &previous.fields, &current.fields, "input field")
+        }
+        (TypeKind::Enum(previous), TypeKind::Enum(current)) => {
+            let previous_values: Vec<String> = previous.values.iter().map(|value| value.node.value.node.to_string()).collect();
+            let current_values: Vec<String> = current.values.iter().map(|value| value.node.value.node.to_string()).collect();
+
+            for value in previous_values.iter() {
+                if !current_values.contains(value) {
+                    changes.push(change(&format!("{}.{}", type_name, value), "enum value removed".into(), true));
+                }
+            }
+
+            for value in current_values.iter() {
+                if !previous_values.contains(value) {
+                    changes.push(change(&format!("{}.{}", type_name, value), "enum value added".into(), false));
+                }
+            }
+        }
+        (TypeKind::Union(previous), TypeKind::Union(current)) => {
+            let previous_members: Vec<String> = previous.members.iter().map(|member| member.node.to_string()).collect();
+            let current_members: Vec<String> = current.members.iter().map(|member| member.node.to_string()).collect();
+
+            if previous_members.ne(&current_members) {
+                changes.push(change(type_name, "union members changed".into(), true));
+            }
+        }
+        _ => changes.push(change(type_name, "type kind changed".into(), true)),
+    }
+}
+
+fn compare_fields(
+    changes: &mut Vec<SdlChange>,
+    type_name: &str,
+    previous: &[Positioned<FieldDefinition>],
+    current: &[Positioned<FieldDefinition>],
+) {
+    let find = |fields: &'_ [Positioned<FieldDefinition>], name: &str| -> Option<FieldDefinition> {
+        fields
+            .iter()
+            .find(|field| field.node.name.node.eq(name))
+            .map(|field| field.node.clone())
+    };
+
+    for field in previous.iter() {
+        if find(current, &field.node.name.node).is_none() {
+            changes.push(change(&format!("{}.{}", type_name, field.node.name.node), "field removed".into(), true));
+        }
+    }
+
+    for field in current.iter() {
+        let field = &field.node;
+        let coordinate = format!("{}.{}", type_name, field.name.node);
+
+        let previous_field = match find(previous, &field.name.node) {
+            Some(previous_field) => previous_field,
+            None => {
+                changes.push(change(&coordinate, "field added".into(), false));
+                continue;
+            }
+        };
+
+        if previous_field.ty.node.ne(&field.ty.node) {
+            changes.push(change(
+                &coordinate,
+                format!("type changed from {} to {}", previous_field.ty.node, field.ty.node),
+                !is_safe_output_change(&previous_field.ty.node, &field.ty.node),
+            ));
+        }
+
+        compare_input_values(changes, &coordinate, &previous_field.arguments, &field.arguments, "argument");
+    }
+}
+
+// arguments and input object fields, values sent by the client
+fn compare_input_values(
+    changes: &mut Vec<SdlChange>,
+    parent: &str,
+    previous: &[Positioned<InputValueDefinition>],
+    current: &[Positioned<InputValueDefinition>],
+    label: &str,
+) {
+    let coordinate = |name: &str| -> String {
+        if label.eq("argument") {
+            format!("{}({}:)", parent, name)
+        } else {
+            format!("{}.{}", parent, name)
+        }
+    };
+
+    let find = |values: &'_ [Positioned<InputValueDefinition>], name: &str| -> Option<InputValueDefinition> {
+        values
+            .iter()
+            .find(|value| value.node.name.node.eq(name))
+            .map(|value| value.node.clone())
+    };
+
+    for value in previous.iter() {
+        if find(current, &value.node.name.node).is_none() {
+            changes.push(change(&coordinate(&value.node.name.node), format!("{} removed", label), true));
+        }
+    }
+
+    for value in current.iter() {
+        let value = &value.node;
+
+        let previous_value = match find(previous, &value.name.node) {
+            Some(previous_value) => previous_value,
+            None => {
+                let required = !value.ty.node.nullable && value.default_value.is_none();
+
+                let description = if required {
+                    format!("required {} added", label)
+                } else {
+                    format!("{} added", label)
+                };
+
+                changes.push(change(&coordinate(&value.name.node), description, required));
+                continue;
+            }
+        };
+
+        if previous_value.ty.node.ne(&value.ty.node) {
+            // values clients sent before must still be accepted
+            changes.push(change(
+                &coordinate(&value.name.node),
+                format!("type changed from {} to {}", previous_value.ty.node, value.ty.node),
+                !is_safe_output_change(&value.ty.node, &previous_value.ty.node),
+            ));
+        }
+    }
+}
+
+// a client reading the field still gets a value it can handle
+fn is_safe_output_change(previous: &Type, current: &Type) -> bool {
+    if !previous.nullable && current.nullable {
+        return false;
+    }
+
+    match (&previous.base, &current.base) {
+        (BaseType::Named(previous), BaseType::Named(current)) => previous.eq(current),
+        (BaseType::List(previous), BaseType::List(current)) => is_safe_output_change(previous, current),
+        _ => false,
+    }
+}
+
+fn change(coordinate: &str, description: String, breaking: bool) -> SdlChange {
+    SdlChange {
+        coordinate: coordinate.into(),
+        description,
+        breaking,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare_sdl;
+
+    const PREVIOUS: &str = r#"
+        type Albums {
+            albumId: Int!
+            title: String!
+            artist: Artists
+        }
+
+        type Artists {
+            artistId: Int!
+            name: String
+        }
+
+        input AlbumsFilter {
+            title: StringFilter
+        }
+
+        input StringFilter {
+            eq: String
+        }
+
+        type QueryRoot {
+            albums(filters: AlbumsFilter): [Albums!]!
+        }
+    "#;
+
+    // coordinates and whether they are breaking, in the order they are reported
+    fn classify(current: &str) -> Vec<(String, bool)> {
+        compare_sdl(PREVIOUS, current)
+            .unwrap()
+            .into_iter()
+            .map(|change| (change.coordinate, change.breaking))
+            .collect()
+    }
+
+    #[test]
+    fn same_schema_has_no_changes() {
+        assert!(classify(PREVIOUS).is_empty());
+    }
+
+    #[test]
+    fn removals_are_breaking_and_additions_are_not() {
+        let current = PREVIOUS
+            .replace("            artist: Artists\n", "            genre: String\n")
+            .replace("input StringFilter", "type Genres { name: String }\n\ninput StringFilter");
+
+        assert_eq!(
+            classify(&current),
+            vec![
+                ("Albums.artist".to_string(), true),
+                ("Albums.genre".to_string(), false),
+                ("Genres".to_string(), false),
+            ]
+        );
+
+        let current = PREVIOUS.replace("type Artists {\n            artistId: Int!\n            name: String\n        }", "");
+
+        assert_eq!(classify(&current), vec![("Artists".to_string(), true)]);
+    }
+
+    #[test]
+    fn output_fields_must_not_become_nullable() {
+        let nullable = PREVIOUS.replace("title: String!", "title: String");
+        let not_null = PREVIOUS.replace("name: String\n", "name: String!\n");
+
+        assert_eq!(classify(&nullable), vec![("Albums.title".to_string(), true)]);
+        assert_eq!(classify(&not_null), vec![("Artists.name".to_string(), false)]);
+    }
+
+    #[test]
+    fn inputs_must_not_become_required() {
+        let required_field = PREVIOUS.replace("title: StringFilter", "title: StringFilter!");
+        let optional_argument = PREVIOUS.replace("(filters: AlbumsFilter)", "(filters: AlbumsFilter, limit: Int)");
+        let required_argument = PREVIOUS.replace("(filters: AlbumsFilter)", "(filters: AlbumsFilter, limit: Int!)");
+        let defaulted_argument = PREVIOUS.replace("(filters: AlbumsFilter)", "(filters: AlbumsFilter, limit: Int! = 10)");
+
+        assert_eq!(classify(&required_field), vec![("AlbumsFilter.title".to_string(), true)]);
+        assert_eq!(classify(&optional_argument), vec![("QueryRoot.albums(limit:)".to_string(), false)]);
+        assert_eq!(classify(&required_argument), vec![("QueryRoot.albums(limit:)".to_string(), true)]);
+        assert_eq!(classify(&defaulted_argument), vec![("QueryRoot.albums(limit:)".to_string(), false)]);
+    }
+
+    #[test]
+    fn changed_named_types_are_breaking() {
+        let current = PREVIOUS.replace("albumId: Int!", "albumId: String!");
+
+        assert_eq!(classify(&current), vec![("Albums.albumId".to_string(), true)]);
+    }
+}
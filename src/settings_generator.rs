@@ -0,0 +1,140 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub const SETTINGS_FILE_NAME: &str = "settings.toml";
+
+// `src/settings.rs` of a generated binary, runtime settings read from the environment and
+// an optional file, so the same build can be deployed anywhere
+pub fn generate_settings() -> TokenStream {
+    let settings_file_name = SETTINGS_FILE_NAME;
+
+    quote! {
+        use serde::Deserialize;
+        use std::{net::SocketAddr, str::FromStr};
+
+        /// Runtime settings of the server.
+        ///
+        /// Every setting is read from the environment variable of the same name in upper case,
+        /// then from the file named by `SETTINGS_FILE` (`settings.toml` by default, optional),
+        /// then falls back to its default.
+        #[derive(Debug)]
+        pub struct Settings {
+            /// `DATABASE_URL`, defaults to `sqlite://../chinook.db`
+            pub database_url: String,
+            /// `DATABASE_POOL_SIZE`, maximum number of connections, defaults to 10
+            pub database_pool_size: u32,
+            /// `BIND_ADDRESS`, defaults to `0.0.0.0:8000`
+            pub bind_address: SocketAddr,
+            /// `LOG_LEVEL`, one of `error`, `warn`, `info`, `debug` and `trace`, defaults to `debug`
+            pub log_level: tracing::Level,
+            /// `PLAYGROUND_ENABLED`, defaults to `true`
+            pub playground_enabled: bool,
+            /// `PLAYGROUND_PATH`, defaults to `/`, GraphQL is always served on `POST /`
+            pub playground_path: String,
+        }
+
+        #[derive(Deserialize, Default)]
+        #[serde(deny_unknown_fields)]
+        struct SettingsFile {
+            database_url: Option<String>,
+            database_pool_size: Option<u32>,
+            bind_address: Option<String>,
+            log_level: Option<String>,
+            playground_enabled: Option<bool>,
+            playground_path: Option<String>,
+        }
+
+        impl Settings {
+            /// Reads the settings, every invalid one is reported.
+            pub fn load() -> Result<Self, Vec<String>> {
+                let mut errors: Vec<String> = Vec::new();
+
+                let file = read_file(&mut errors);
+
+                let settings = Self {
+                    database_url: read("DATABASE_URL", file.database_url, "sqlite://../chinook.db".into(), &mut errors),
+                    database_pool_size: read(
+                        "DATABASE_POOL_SIZE",
+                        file.database_pool_size.map(|size| size.to_string()),
+                        10,
+                        &mut errors,
+                    ),
+                    bind_address: read(
+                        "BIND_ADDRESS",
+                        file.bind_address,
+                        SocketAddr::from(([0, 0, 0, 0], 8000)),
+                        &mut errors,
+                    ),
+                    log_level: read("LOG_LEVEL", file.log_level, tracing::Level::DEBUG, &mut errors),
+                    playground_enabled: read(
+                        "PLAYGROUND_ENABLED",
+                        file.playground_enabled.map(|enabled| enabled.to_string()),
+                        true,
+                        &mut errors,
+                    ),
+                    playground_path: read("PLAYGROUND_PATH", file.playground_path, "/".into(), &mut errors),
+                };
+
+                if settings.database_url.is_empty() {
+                    errors.push("DATABASE_URL: cannot be empty".into());
+                }
+
+                if settings.database_pool_size.eq(&0) {
+                    errors.push("DATABASE_POOL_SIZE: has to be at least 1".into());
+                }
+
+                if !settings.playground_path.starts_with('/') {
+                    errors.push(format!("PLAYGROUND_PATH: `{}` has to start with `/`", settings.playground_path));
+                }
+
+                if errors.is_empty() {
+                    Ok(settings)
+                } else {
+                    Err(errors)
+                }
+            }
+
+            /// Path of the playground when it is enabled.
+            pub fn playground(&self) -> Option<&str> {
+                self.playground_enabled.then(|| self.playground_path.as_str())
+            }
+        }
+
+        // a missing file is only an error when `SETTINGS_FILE` names it
+        fn read_file(errors: &mut Vec<String>) -> SettingsFile {
+            let explicit = std::env::var("SETTINGS_FILE").ok();
+            let path = explicit.clone().unwrap_or_else(|| #settings_file_name.into());
+
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(error) => {
+                    if explicit.is_some() || error.kind().ne(&std::io::ErrorKind::NotFound) {
+                        errors.push(format!("SETTINGS_FILE: cannot read `{}`: {}", path, error));
+                    }
+
+                    return SettingsFile::default();
+                }
+            };
+
+            toml::from_str(&content).unwrap_or_else(|error| {
+                errors.push(format!("SETTINGS_FILE: invalid `{}`: {}", path, error));
+
+                SettingsFile::default()
+            })
+        }
+
+        // the environment wins over the file
+        fn read<T: FromStr>(name: &str, file_value: Option<String>, default: T, errors: &mut Vec<String>) -> T {
+            let value = match std::env::var(name).ok().or(file_value) {
+                Some(value) => value,
+                None => return default,
+            };
+
+            value.parse().unwrap_or_else(|_| {
+                errors.push(format!("{}: invalid value `{}`", name, value));
+
+                default
+            })
+        }
+    }
+}
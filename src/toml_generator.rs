@@ -1,3 +1,7 @@
+use crate::{
+    config::{OutputMode, WebFramework},
+    error::{GeneratorError, Result},
+};
 use serde_derive::Serialize;
 use std::collections::BTreeMap;
 use std::fs;
@@ -16,12 +20,8 @@ pub struct DependencyInfo {
 }
 
 impl TomlStructure {
-    pub fn new(name: String) -> Self {
-        let mut package: BTreeMap<String, String> = BTreeMap::new();
-
-        package.insert("name".into(), name);
-        package.insert("version".into(), "0.1.0".into());
-        package.insert("edition".into(), "2021".into());
+    pub fn new(name: String, web_framework: WebFramework, output: OutputMode) -> Self {
+        let package = Self::new_package(name);
 
         let mut dependencies: BTreeMap<String, DependencyInfo> = BTreeMap::new();
         dependencies.insert(
@@ -42,28 +42,35 @@ impl TomlStructure {
             },
         );
         dependencies.insert(
-            "async-graphql-poem".into(),
+            "tokio".into(),
             DependencyInfo {
-                version: "3.0.38".into(),
-                features: None,
+                version: "1.17.0".into(),
+                features: Some(vec!["macros".into(), "rt-multi-thread".into()]),
             },
         );
+
+        // the integration crate follows the async-graphql version
+        let (integration, framework, framework_version) = match web_framework {
+            WebFramework::Poem => ("async-graphql-poem", "poem", "1.3.29"),
+            WebFramework::Axum => ("async-graphql-axum", "axum", "0.5.4"),
+            WebFramework::ActixWeb => ("async-graphql-actix-web", "actix-web", "4.0.1"),
+        };
+
         dependencies.insert(
-            "tokio".into(),
+            integration.into(),
             DependencyInfo {
-                version: "1.17.0".into(),
-                features: Some(vec!["macros".into(), "rt-multi-thread".into()]),
+                version: "3.0.38".into(),
+                features: None,
             },
         );
         dependencies.insert(
-            "poem".into(),
+            framework.into(),
             DependencyInfo {
-                version: "1.3.29".into(),
+                version: framework_version.into(),
                 features: None,
             },
         );
 
-
         dependencies.insert(
             "async-trait".into(),
             DependencyInfo {
@@ -73,43 +80,145 @@ impl TomlStructure {
         );
 
         dependencies.insert(
-            "tracing".into(),
+            "itertools".into(),
             DependencyInfo {
-                version: "0.1.34".into(),
+                version: "0.10.3".into(),
                 features: None,
             },
         );
 
+        // only used by `main.rs` and its settings
+        if output.eq(&OutputMode::Binary) {
+            dependencies.insert(
+                "serde".into(),
+                DependencyInfo {
+                    version: "1.0.137".into(),
+                    features: Some(vec!["derive".into()]),
+                },
+            );
+
+            dependencies.insert(
+                "toml".into(),
+                DependencyInfo {
+                    version: "0.5.9".into(),
+                    features: None,
+                },
+            );
+
+            dependencies.insert(
+                "tracing".into(),
+                DependencyInfo {
+                    version: "0.1.34".into(),
+                    features: None,
+                },
+            );
+
+            dependencies.insert(
+                "tracing-subscriber".into(),
+                DependencyInfo {
+                    version: "0.3.11".into(),
+                    features: None,
+                },
+            );
+
+            dependencies.insert(
+                "serde_json".into(),
+                DependencyInfo {
+                    version: "1.0".into(),
+                    features: None,
+                },
+            );
+        }
+
+        Self {
+            package,
+            dependencies,
+        }
+    }
+
+    // companion client crate, see `client_generator`
+    pub fn new_client(name: String) -> Self {
+        let package = Self::new_package(name);
+
+        let mut dependencies: BTreeMap<String, DependencyInfo> = BTreeMap::new();
+        dependencies.insert(
+            "serde".into(),
+            DependencyInfo {
+                version: "1.0.137".into(),
+                features: Some(vec!["derive".into()]),
+            },
+        );
+
         dependencies.insert(
-            "tracing-subscriber".into(),
+            "serde_json".into(),
             DependencyInfo {
-                version: "0.3.11".into(),
+                version: "1.0".into(),
                 features: None,
             },
         );
 
         dependencies.insert(
-            "itertools".into(),
+            "async-trait".into(),
             DependencyInfo {
-                version: "0.10.3".into(),
+                version: "0.1.53".into(),
                 features: None,
             },
         );
 
+        dependencies.insert(
+            "reqwest".into(),
+            DependencyInfo {
+                version: "0.11.10".into(),
+                features: Some(vec!["json".into()]),
+            },
+        );
+
         Self {
             package,
             dependencies,
         }
     }
+
+    fn new_package(name: String) -> BTreeMap<String, String> {
+        let mut package: BTreeMap<String, String> = BTreeMap::new();
+
+        package.insert("name".into(), name);
+        package.insert("version".into(), "0.1.0".into());
+        package.insert("edition".into(), "2021".into());
+
+        package
+    }
 }
 
 // TODO options sqlite, mysql, pgsql
-pub fn write_toml(project_path: &std::path::Path, name: &String) -> std::io::Result<()> {
-    let file_path = project_path.join("Cargo.toml");
+pub fn generate_toml(name: &str, web_framework: WebFramework, output: OutputMode) -> Result<String> {
+    let data = TomlStructure::new(name.into(), web_framework, output);
 
-    let data = TomlStructure::new(name.clone());
+    to_toml("Cargo.toml", &data)
+}
+
+pub fn generate_client_toml(name: &str) -> Result<String> {
+    let data = TomlStructure::new_client(name.into());
+
+    to_toml("client/Cargo.toml", &data)
+}
+
+fn to_toml(file: &str, data: &TomlStructure) -> Result<String> {
+    toml::to_string_pretty(data).map_err(|error| GeneratorError::Serialization {
+        file: file.into(),
+        message: error.to_string(),
+    })
+}
+
+pub fn write_toml(
+    project_path: &std::path::Path,
+    name: &str,
+    web_framework: WebFramework,
+    output: OutputMode,
+) -> Result<()> {
+    let file_path = project_path.join("Cargo.toml");
 
-    fs::write(file_path, toml::to_string_pretty(&data).unwrap())?;
+    fs::write(file_path, generate_toml(name, web_framework, output)?)?;
 
     Ok(())
 }
@@ -1,10 +1,15 @@
 use proc_macro2::TokenStream;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ColumnMeta {
     pub column_name: String,      // snake_case
+    pub database_name: String,    // as in the database
+    pub field_name: String,       // snake_case, GraphQL field
     pub column_enum_name: String, // CamelCase
+    #[serde(with = "super::token_serde")]
     pub column_type: TokenStream,
+    #[serde(with = "super::token_serde")]
     pub column_filter_type: TokenStream,
     pub not_null: bool,
     pub is_primary_key: bool,
@@ -15,6 +20,7 @@ impl std::fmt::Debug for ColumnMeta {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ColumnMeta")
         .field("column_name", &self.column_name)
+        .field("field_name", &self.field_name)
         .field("not_null", &self.not_null)
         .field("column_type", &self.column_type.to_string())
         .finish()
@@ -1,25 +1,41 @@
+use heck::ToUpperCamelCase;
 use proc_macro2::TokenStream;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ForeignKeyMeta {
 
     pub source_table_name: String,   // CamelCase
     pub source_table_module: String, // snake_case
     pub source_columns: Vec<String>,     // Vec<CamelCase>
+    #[serde(with = "super::token_serde::vec")]
     pub source_column_types: Vec<TokenStream>,
 
     pub destination_table_name: String,   // CamelCase
     pub destination_table_module: String, // snake_case
     pub destination_columns: Vec<String>, // Vec<CamelCase>
+    #[serde(with = "super::token_serde::vec")]
     pub destination_column_types: Vec<TokenStream>,
+
+    pub relation_name: String,         // snake_case, field on source table
+    pub reverse_relation_name: String, // snake_case, field on destination table
+
+    pub one_to_one: bool, // reverse side resolves to a single model
+
+    #[serde(skip)]
+    pub declared_relation: Option<usize>, // index in `[[relations]]`, only known while discovering
 }
 
 impl ForeignKeyMeta {
-    pub fn is_reverse(self: &Self, table_name: &String) -> bool {
+    pub fn is_reverse(&self, table_name: &String) -> bool {
         self.destination_table_name.eq(table_name)
     }
 
-    pub fn is_optional(self: &Self, is_reverse: bool) -> bool {
+    pub fn is_self_reference(&self) -> bool {
+        self.source_table_name.eq(&self.destination_table_name)
+    }
+
+    pub fn is_optional(&self, is_reverse: bool) -> bool {
         let column_types = if is_reverse {&self.destination_column_types} else {&self.source_column_types};
 
         column_types
@@ -27,15 +43,15 @@ impl ForeignKeyMeta {
             .any(|column_type: &TokenStream| column_type.to_string().starts_with("Option"))
     }
 
-    pub fn is_source_optional(self: &Self) -> bool {
+    pub fn is_source_optional(&self) -> bool {
         self.is_optional(false)
     }
 
-    pub fn is_destination_optional(self: &Self) -> bool {
+    pub fn is_destination_optional(&self) -> bool {
         self.is_optional(true)
     }
 
-    pub fn get_optional_columns(self: &Self, is_reverse: bool) -> Vec<bool> {
+    pub fn get_optional_columns(&self, is_reverse: bool) -> Vec<bool> {
         let column_types = if is_reverse {&self.destination_column_types} else {&self.source_column_types};
 
         column_types
@@ -44,13 +60,47 @@ impl ForeignKeyMeta {
             .collect()
     }
 
-    pub fn get_source_optional(self: &Self) -> Vec<bool> {
+    pub fn get_source_optional(&self) -> Vec<bool> {
         self.get_optional_columns(false)
     }
 
-    pub fn get_destination_optional(self: &Self) -> Vec<bool> {
+    pub fn get_destination_optional(&self) -> Vec<bool> {
         self.get_optional_columns(true)
     }
+
+    pub fn is_list(&self, is_reverse: bool) -> bool {
+        is_reverse && !self.one_to_one
+    }
+
+    // both keys start from the same columns of the same table
+    pub fn has_source_columns_of(&self, other: &ForeignKeyMeta) -> bool {
+        let mut columns: Vec<&String> = self.source_columns.iter().collect();
+        let mut other_columns: Vec<&String> = other.source_columns.iter().collect();
+        columns.sort();
+        other_columns.sort();
+
+        self.source_table_module.eq(&other.source_table_module) && columns.eq(&other_columns)
+    }
+
+    pub fn get_relation_name(&self, is_reverse: bool) -> &String {
+        if is_reverse {&self.reverse_relation_name} else {&self.relation_name}
+    }
+
+    // FK columns are part of the name so multiple keys between the same tables do not clash
+    pub fn get_key_name(&self, is_reverse: bool) -> String {
+        let columns = self
+            .source_columns
+            .iter()
+            .map(|column: &String| column.to_upper_camel_case())
+            .collect::<Vec<String>>()
+            .join("");
+
+        if is_reverse {
+            format!("{}{}{}FK", self.destination_table_name, self.source_table_name, columns)
+        } else {
+            format!("{}{}{}FK", self.source_table_name, columns, self.destination_table_name)
+        }
+    }
 }
 
 impl std::fmt::Debug for ForeignKeyMeta {
@@ -65,6 +115,10 @@ impl std::fmt::Debug for ForeignKeyMeta {
         .field("destination_columns", &self.destination_columns)
         .field("destination_column_types", &self.destination_column_types)
         .field("destination_optional", &self.is_optional(true))
+
+        .field("relation_name", &self.relation_name)
+        .field("reverse_relation_name", &self.reverse_relation_name)
+        .field("one_to_one", &self.one_to_one)
         .finish()
     }
-}
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use super::foreign_key_meta::ForeignKeyMeta;
+use heck::ToUpperCamelCase;
+use serde_derive::{Deserialize, Serialize};
+
+// Relation through a junction table (primary key made entirely of two foreign keys).
+// Describes one direction, every junction table yields two of them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManyToManyMeta {
+    pub junction_table_name: String,   // CamelCase
+    pub junction_table_module: String, // snake_case
+
+    // junction -> table that exposes the relation
+    pub source_foreign_key: ForeignKeyMeta,
+    // junction -> table the relation resolves to
+    pub destination_foreign_key: ForeignKeyMeta,
+
+    pub relation_name: String, // snake_case
+}
+
+impl ManyToManyMeta {
+    pub fn is_source(&self, table_name: &String) -> bool {
+        self.source_foreign_key.destination_table_name.eq(table_name)
+    }
+
+    pub fn get_key_name(&self) -> String {
+        let columns = self
+            .destination_foreign_key
+            .source_columns
+            .iter()
+            .map(|column: &String| column.to_upper_camel_case())
+            .collect::<Vec<String>>()
+            .join("");
+
+        format!("{}{}M2M", self.junction_table_name, columns)
+    }
+}
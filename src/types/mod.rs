@@ -1,8 +1,13 @@
 pub mod column_meta;
 pub mod table_meta;
 pub mod foreign_key_meta;
+pub mod many_to_many_meta;
 pub mod primary_key_meta;
+pub mod schema_snapshot;
+pub mod token_serde;
 
 pub use column_meta::ColumnMeta;
 pub use table_meta::TableMeta;
-pub use foreign_key_meta::ForeignKeyMeta;
\ No newline at end of file
+pub use foreign_key_meta::ForeignKeyMeta;
+pub use many_to_many_meta::ManyToManyMeta;
+pub use schema_snapshot::SchemaSnapshot;
\ No newline at end of file
@@ -0,0 +1,142 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::table_meta::TableMeta;
+use crate::error::{GeneratorError, Result};
+
+// serializable view of the schema as it is exposed through GraphQL
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    pub tables: Vec<TableSnapshot>,
+    // everything the code generators need
+    pub tables_meta: Vec<TableMeta>,
+    // sources of the `orm` module keyed by file name, only stored by `cargo run snapshot`
+    #[serde(default)]
+    pub orm_files: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub table_name: String, // as in the database
+    pub entity_name: String, // GraphQL object name
+    pub columns: Vec<ColumnSnapshot>,
+    pub relations: Vec<RelationSnapshot>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ColumnSnapshot {
+    pub column_name: String, // snake_case
+    pub field_name: String, // GraphQL field
+    pub column_type: String, // rust type without `Option`
+    pub not_null: bool,
+    pub is_primary_key: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RelationSnapshot {
+    pub field_name: String, // snake_case, GraphQL field
+    pub destination_table: String,
+    pub is_list: bool,
+}
+
+impl SchemaSnapshot {
+    pub fn new(tables_meta: &[TableMeta]) -> Self {
+        let tables: Vec<TableSnapshot> = tables_meta
+            .iter()
+            .map(|table: &TableMeta| TableSnapshot {
+                table_name: table.table_name.clone(),
+                entity_name: table.entity_name.clone(),
+                columns: table
+                    .columns
+                    .iter()
+                    .map(|column| ColumnSnapshot {
+                        column_name: column.column_name.clone(),
+                        field_name: column.field_name.clone(),
+                        column_type: column.column_filter_type.to_string(),
+                        not_null: column.not_null,
+                        is_primary_key: column.is_primary_key,
+                    })
+                    .collect(),
+                relations: get_relations(table),
+            })
+            .collect();
+
+        Self {
+            tables,
+            tables_meta: tables_meta.to_vec(),
+            orm_files: BTreeMap::new(),
+        }
+    }
+
+    // the sea-orm entities cannot be derived from the metadata, build scripts generate them from these sources
+    pub fn with_orm_files(mut self, orm_files: BTreeMap<String, String>) -> Self {
+        self.orm_files = orm_files;
+        self
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+
+        serde_json::from_str(&content).map_err(|error| GeneratorError::InvalidSnapshot(error.to_string()))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|error| GeneratorError::Serialization {
+            file: "schema snapshot".into(),
+            message: error.to_string(),
+        })
+    }
+
+    pub fn get_table(&self, table_name: &str) -> Option<&TableSnapshot> {
+        self.tables
+            .iter()
+            .find(|table: &&TableSnapshot| table.table_name.eq(table_name))
+    }
+}
+
+impl TableSnapshot {
+    pub fn get_column(&self, column_name: &str) -> Option<&ColumnSnapshot> {
+        self.columns
+            .iter()
+            .find(|column: &&ColumnSnapshot| column.column_name.eq(column_name))
+    }
+
+    pub fn get_relation(&self, field_name: &str) -> Option<&RelationSnapshot> {
+        self.relations
+            .iter()
+            .find(|relation: &&RelationSnapshot| relation.field_name.eq(field_name))
+    }
+}
+
+// a self referencing key appears in both directions
+fn get_relations(table: &TableMeta) -> Vec<RelationSnapshot> {
+    let mut relations: Vec<RelationSnapshot> = Vec::new();
+
+    for fk in table.foreign_keys.iter() {
+        if fk.source_table_name.eq(&table.entity_name) {
+            relations.push(RelationSnapshot {
+                field_name: fk.get_relation_name(false).clone(),
+                destination_table: fk.destination_table_name.clone(),
+                is_list: fk.is_list(false),
+            });
+        }
+
+        if fk.destination_table_name.eq(&table.entity_name) {
+            relations.push(RelationSnapshot {
+                field_name: fk.get_relation_name(true).clone(),
+                destination_table: fk.source_table_name.clone(),
+                is_list: fk.is_list(true),
+            });
+        }
+    }
+
+    for relation in table.many_to_many.iter() {
+        relations.push(RelationSnapshot {
+            field_name: relation.relation_name.clone(),
+            destination_table: relation.destination_foreign_key.destination_table_name.clone(),
+            is_list: true,
+        });
+    }
+
+    relations
+}
@@ -1,10 +1,27 @@
 use super::column_meta::ColumnMeta;
 use super::foreign_key_meta::ForeignKeyMeta;
+use super::many_to_many_meta::ManyToManyMeta;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TableMeta {
+    pub table_name: String, // as in the database
     pub entity_name: String, // CamelCase
     pub entity_module: String, // snake_case
+    pub filter_name: String, // GraphQL input name
+    pub paginated_result_name: String, // GraphQL object name
+    pub filter_template: String, // `naming.filter`, `{}` is replaced by the entity name
+    pub paginated_result_template: String, // `naming.paginated_result`
     pub columns: Vec<ColumnMeta>,
     pub foreign_keys: Vec<ForeignKeyMeta>,
-}
\ No newline at end of file
+    pub many_to_many: Vec<ManyToManyMeta>,
+}
+
+impl TableMeta {
+    // the filter and result names follow the entity name
+    pub fn rename(&mut self, entity_name: String) {
+        self.filter_name = self.filter_template.replace("{}", &entity_name);
+        self.paginated_result_name = self.paginated_result_template.replace("{}", &entity_name);
+        self.entity_name = entity_name;
+    }
+}
@@ -0,0 +1,33 @@
+use proc_macro2::TokenStream;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+// token streams are stored as their source text, e.g. `Option < i32 >`
+pub fn serialize<S: Serializer>(tokens: &TokenStream, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&tokens.to_string())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TokenStream, D::Error> {
+    let source = String::deserialize(deserializer)?;
+
+    source.parse().map_err(|_| D::Error::custom(format!("invalid rust type `{}`", source)))
+}
+
+pub mod vec {
+    use proc_macro2::TokenStream;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(tokens: &[TokenStream], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(tokens.iter().map(|tokens: &TokenStream| tokens.to_string()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<TokenStream>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|source: &String| {
+                source
+                    .parse()
+                    .map_err(|_| D::Error::custom(format!("invalid rust type `{}`", source)))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    graphql::entities::{get_hierarchy_relation_names, get_relation_directions},
+    scalar_mapping::{get_scalar, FILTERS, SCALARS},
+    types::{ColumnMeta, ManyToManyMeta, TableMeta},
+};
+use heck::ToLowerCamelCase;
+
+const HEADER: &str = "// Generated from the database schema, do not edit.\n\
+// Fields added through `src/extensions` are not included.\n";
+
+pub fn generate_typescript(tables_meta: &[TableMeta]) -> String {
+    let mut output: Vec<String> = vec![HEADER.into()];
+
+    output.push("export type Maybe<T> = T | null;\n".into());
+
+    // the JSON type names are TypeScript types as well
+    let scalars: Vec<String> = SCALARS
+        .iter()
+        .map(|(name, type_name)| format!("  {}: {};", name, type_name))
+        .collect();
+
+    output.push(format!("export type Scalars = {{\n{}\n}};\n", scalars.join("\n")));
+
+    output.push(
+        "export interface TypeFilter<T> {\n  \
+        eq?: Maybe<T>;\n  \
+        ne?: Maybe<T>;\n  \
+        gt?: Maybe<T>;\n  \
+        gte?: Maybe<T>;\n  \
+        lt?: Maybe<T>;\n  \
+        lte?: Maybe<T>;\n  \
+        isIn?: Maybe<Array<T>>;\n  \
+        isNotIn?: Maybe<Array<T>>;\n  \
+        isNull?: Maybe<Scalars['Boolean']>;\n\
+        }\n"
+            .into(),
+    );
+
+    for (filter_name, scalar) in FILTERS.iter() {
+        output.push(format!("export type {} = TypeFilter<Scalars['{}']>;", filter_name, scalar));
+    }
+
+    output.push(String::new());
+
+    output.push(
+        "export interface PaginationInput {\n  \
+        limit: Scalars['Int'];\n  \
+        page: Scalars['Int'];\n\
+        }\n"
+            .into(),
+    );
+
+    for table in tables_meta.iter() {
+        output.push(generate_entity_interface(table));
+        output.push(generate_filter_interface(table));
+        output.push(generate_paginated_result_interface(table));
+    }
+
+    output.join("\n")
+}
+
+fn generate_entity_interface(table: &TableMeta) -> String {
+    let mut fields: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column: &ColumnMeta| {
+            let (scalar, _) = get_scalar(&column.column_filter_type.to_string());
+
+            let field_type = format!("Scalars['{}']", scalar);
+
+            let field_type = if column.not_null { field_type } else { format!("Maybe<{}>", field_type) };
+
+            format!("  {}: {};", column.field_name.to_lower_camel_case(), field_type)
+        })
+        .collect();
+
+    for (fk, reverse) in get_relation_directions(table).into_iter() {
+        let destination = if reverse { &fk.source_table_name } else { &fk.destination_table_name };
+
+        let field_type = if fk.is_list(reverse) {
+            format!("Array<{}>", destination)
+        } else if reverse || fk.is_optional(reverse) {
+            format!("Maybe<{}>", destination)
+        } else {
+            destination.clone()
+        };
+
+        fields.push(format!("  {}: {};", fk.get_relation_name(reverse).to_lower_camel_case(), field_type));
+    }
+
+    for relation in table.many_to_many.iter() {
+        let ManyToManyMeta { relation_name, destination_foreign_key, .. } = relation;
+
+        fields.push(format!(
+            "  {}: Array<{}>;",
+            relation_name.to_lower_camel_case(),
+            destination_foreign_key.destination_table_name
+        ));
+    }
+
+    for (_, ancestors, descendants) in get_hierarchy_relation_names(table).into_iter() {
+        fields.push(format!("  {}: Array<{}>;", ancestors.to_lower_camel_case(), table.entity_name));
+        fields.push(format!("  {}: Array<{}>;", descendants.to_lower_camel_case(), table.entity_name));
+    }
+
+    format!("export interface {} {{\n{}\n}}\n", table.entity_name, fields.join("\n"))
+}
+
+fn generate_filter_interface(table: &TableMeta) -> String {
+    let mut fields: Vec<String> = vec![
+        format!("  or?: Maybe<Array<{}>>;", table.filter_name),
+        format!("  and?: Maybe<Array<{}>>;", table.filter_name),
+    ];
+
+    for column in table.columns.iter() {
+        if let (_, Some(filter_name)) = get_scalar(&column.column_filter_type.to_string()) {
+            fields.push(format!("  {}?: Maybe<{}>;", column.field_name.to_lower_camel_case(), filter_name));
+        }
+    }
+
+    format!("export interface {} {{\n{}\n}}\n", table.filter_name, fields.join("\n"))
+}
+
+fn generate_paginated_result_interface(table: &TableMeta) -> String {
+    format!(
+        "export interface {} {{\n  data: Array<{}>;\n  pages: Scalars['Int'];\n  current: Scalars['Int'];\n}}\n",
+        table.paginated_result_name, table.entity_name
+    )
+}